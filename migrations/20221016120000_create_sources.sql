@@ -1,9 +1,9 @@
-create table sources
+CREATE TABLE IF NOT EXISTS sources
 (
     id         INTEGER not null
         primary key autoincrement
         unique,
     url        TEXT    not null,
     fetch_date INTEGER not null,
-    source     INT    not null
-);
\ No newline at end of file
+    source     INTEGER not null
+);
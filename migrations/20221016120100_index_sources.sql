@@ -0,0 +1,9 @@
+-- Keeps the first sighting of every URL, which the unique index below relies on
+DELETE
+FROM sources
+WHERE id NOT IN (SELECT MIN(id)
+                 FROM sources
+                 GROUP BY source, url);
+
+CREATE UNIQUE INDEX IF NOT EXISTS sources_source_url ON sources (source, url);
+CREATE INDEX IF NOT EXISTS sources_fetch_date ON sources (fetch_date);
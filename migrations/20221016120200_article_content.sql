@@ -0,0 +1,5 @@
+-- Content of delivered articles, compared against later versions to notice edits
+ALTER TABLE sources ADD COLUMN fingerprint INTEGER;
+ALTER TABLE sources ADD COLUMN title TEXT;
+ALTER TABLE sources ADD COLUMN body TEXT;
+ALTER TABLE sources ADD COLUMN checked_date INTEGER;
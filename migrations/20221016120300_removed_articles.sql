@@ -0,0 +1,2 @@
+-- When an article was found to be taken down, NULL while it is available
+ALTER TABLE sources ADD COLUMN removed_date INTEGER;
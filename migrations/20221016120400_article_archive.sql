@@ -0,0 +1,7 @@
+-- Remaining embed fields, so that articles can be served without fetching them again
+ALTER TABLE sources ADD COLUMN preview TEXT;
+ALTER TABLE sources ADD COLUMN image TEXT;
+ALTER TABLE sources ADD COLUMN scrape_type TEXT;
+ALTER TABLE sources ADD COLUMN publish_date INTEGER;
+
+CREATE INDEX IF NOT EXISTS sources_source_fetch_date ON sources (source, fetch_date);
@@ -0,0 +1,34 @@
+-- Full-text index over the archived articles, kept in sync with the sources table by triggers
+CREATE VIRTUAL TABLE IF NOT EXISTS sources_search USING fts5
+(
+    title,
+    preview,
+    body,
+    content = 'sources',
+    content_rowid = 'id',
+    tokenize = 'unicode61 remove_diacritics 2'
+);
+
+CREATE TRIGGER IF NOT EXISTS sources_search_insert
+    AFTER INSERT
+    ON sources
+BEGIN
+    INSERT INTO sources_search (rowid, title, preview, body) VALUES (new.id, new.title, new.preview, new.body);
+END;
+
+CREATE TRIGGER IF NOT EXISTS sources_search_delete
+    AFTER DELETE
+    ON sources
+BEGIN
+    INSERT INTO sources_search (sources_search, rowid, title, preview, body) VALUES ('delete', old.id, old.title, old.preview, old.body);
+END;
+
+CREATE TRIGGER IF NOT EXISTS sources_search_update
+    AFTER UPDATE OF title, preview, body
+    ON sources
+BEGIN
+    INSERT INTO sources_search (sources_search, rowid, title, preview, body) VALUES ('delete', old.id, old.title, old.preview, old.body);
+    INSERT INTO sources_search (rowid, title, preview, body) VALUES (new.id, new.title, new.preview, new.body);
+END;
+
+INSERT INTO sources_search (sources_search) VALUES ('rebuild');
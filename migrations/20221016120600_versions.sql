@@ -0,0 +1,10 @@
+-- Client versions announced by changelogs, a version is major when it is the first known one of its major.minor series
+CREATE TABLE IF NOT EXISTS versions
+(
+	id           INTEGER PRIMARY KEY AUTOINCREMENT,
+	version      TEXT    NOT NULL UNIQUE,
+	major        BOOLEAN NOT NULL,
+	release_date INTEGER,
+	url          TEXT    NOT NULL,
+	seen_date    INTEGER NOT NULL
+);
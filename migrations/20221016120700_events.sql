@@ -0,0 +1,15 @@
+-- Dates of events announced by news articles, one event per article
+CREATE TABLE IF NOT EXISTS events
+(
+	id         INTEGER PRIMARY KEY AUTOINCREMENT,
+	source     INTEGER NOT NULL,
+	url        TEXT    NOT NULL,
+	title      TEXT    NOT NULL,
+	start_date INTEGER NOT NULL,
+	end_date   INTEGER,
+	-- Events without a time of day start at midnight UTC
+	all_day    BOOLEAN NOT NULL,
+	UNIQUE (source, url)
+);
+
+CREATE INDEX IF NOT EXISTS events_start_date ON events (start_date);
@@ -0,0 +1,11 @@
+-- Reminders already delivered, so that none is sent twice. Reminders for changed event dates are due again
+CREATE TABLE IF NOT EXISTS sent_reminders
+(
+	event     INTEGER NOT NULL,
+	hook      INTEGER NOT NULL,
+	kind      TEXT    NOT NULL,
+	lead      INTEGER NOT NULL,
+	due_date  INTEGER NOT NULL,
+	sent_date INTEGER NOT NULL,
+	PRIMARY KEY (event, hook, kind, lead, due_date)
+);
@@ -0,0 +1,101 @@
+use chrono::DateTime;
+
+use crate::api::database_queries::Event;
+
+// Octets per line before it is folded, as required by RFC 5545
+const LINE_LENGTH: usize = 75;
+
+// Events without an end are shown as lasting this long
+const DEFAULT_DURATION: i64 = 60 * 60;
+
+/// Renders events as iCalendar document, which calendar apps can subscribe to
+pub fn calendar(events: &[Event], stamp: i64) -> String {
+	let mut lines = vec![
+		"BEGIN:VCALENDAR".to_owned(),
+		"VERSION:2.0".to_owned(),
+		"PRODID:-//wt_event_handler//events//EN".to_owned(),
+		"CALSCALE:GREGORIAN".to_owned(),
+		"X-WR-CALNAME:War Thunder events".to_owned(),
+	];
+	for event in events {
+		lines.push("BEGIN:VEVENT".to_owned());
+		lines.push(format!("UID:{}-{}@wt_event_handler", event.source, event.id));
+		lines.push(format!("DTSTAMP:{}", date_time(stamp)));
+		if event.all_day {
+			// All day events end exclusively on the following day
+			let end = event.end_date.unwrap_or(event.start_date) + 60 * 60 * 24;
+			lines.push(format!("DTSTART;VALUE=DATE:{}", date(event.start_date)));
+			lines.push(format!("DTEND;VALUE=DATE:{}", date(end)));
+		} else {
+			lines.push(format!("DTSTART:{}", date_time(event.start_date)));
+			lines.push(format!("DTEND:{}", date_time(event.end_date.unwrap_or(event.start_date + DEFAULT_DURATION))));
+		}
+		lines.push(format!("SUMMARY:{}", escape(&event.title)));
+		lines.push(format!("DESCRIPTION:{}", escape(&event.url)));
+		lines.push(format!("URL:{}", event.url));
+		lines.push("END:VEVENT".to_owned());
+	}
+	lines.push("END:VCALENDAR".to_owned());
+
+	lines.iter().map(|line| fold(line) + "\r\n").collect()
+}
+
+fn date_time(timestamp: i64) -> String {
+	DateTime::from_timestamp(timestamp, 0).unwrap_or_default().format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+fn date(timestamp: i64) -> String {
+	DateTime::from_timestamp(timestamp, 0).unwrap_or_default().format("%Y%m%d").to_string()
+}
+
+fn escape(text: &str) -> String {
+	text.replace('\\', "\\\\")
+		.replace(';', "\\;")
+		.replace(',', "\\,")
+		// Any line break becomes a single escaped newline, a bare carriage return would end the content line
+		.replace("\r\n", "\\n")
+		.replace(['\r', '\n'], "\\n")
+}
+
+/// Splits long lines into continuation lines starting with a space, without splitting characters
+fn fold(line: &str) -> String {
+	let mut folded = String::new();
+	let mut length = 0;
+	for c in line.chars() {
+		if length + c.len_utf8() > LINE_LENGTH {
+			folded.push_str("\r\n ");
+			length = 1;
+		}
+		folded.push(c);
+		length += c.len_utf8();
+	}
+	folded
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::api::calendar::{calendar, escape, fold};
+	use crate::api::database_queries::Event;
+
+	#[test]
+	fn test_calendar() {
+		let events = [Event {
+			id: 1,
+			source: 0,
+			url: "https://warthunder.com/en/news/7640-event-the-battle-for-arachis-en".to_owned(),
+			title: "The Battle for Arachis, part 1".to_owned(),
+			start_date: 1_665_745_200,
+			end_date: None,
+			all_day: false,
+		}];
+		let calendar = calendar(&events, 1_665_403_200);
+		assert!(calendar.contains("\r\nDTSTART:20221014T110000Z\r\nDTEND:20221014T120000Z\r\n"));
+		assert!(calendar.contains("\r\nSUMMARY:The Battle for Arachis\\, part 1\r\n"));
+		assert!(calendar.lines().all(|line| line.len() <= 76));
+		assert_eq!(escape("Part 1\r\nPart 2\rPart 3\nPart 4"), "Part 1\\nPart 2\\nPart 3\\nPart 4");
+
+		let folded = fold(&"ä".repeat(50));
+		assert!(folded.split("\r\n").all(|line| line.len() <= 75));
+		assert_eq!(folded.replace("\r\n ", ""), "ä".repeat(50));
+	}
+}
\ No newline at end of file
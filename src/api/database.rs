@@ -1,9 +1,10 @@
-use std::str::FromStr;
+use std::fs;
+use std::path::Path;
 use std::sync::Arc;
-use std::sync::atomic::AtomicI64;
+use std::sync::atomic::{AtomicI64, Ordering};
 
-use sqlx::{Executor, SqlitePool};
-use sqlx::sqlite::SqliteConnectOptions;
+use sqlx::SqlitePool;
+use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode};
 
 use crate::api::db_error::DatabaseError;
 
@@ -16,16 +17,87 @@ pub struct Database {
 
 // ONLY IMPL DATABASE INTERNAL THINGS HERE, API ORIENTED FUNCTIONALITY GOES INTO database_queries.rs
 impl Database {
-	pub async fn new() -> Result<Self, DatabaseError> {
-		let options = SqliteConnectOptions::from_str("sqlite::memory:")?
+	/// Opens or creates the database file and brings its schema up to date
+	pub async fn new(path: &str) -> Result<Self, DatabaseError> {
+		if let Some(parent) = Path::new(path).parent() {
+			fs::create_dir_all(parent)?;
+		}
+		let options = SqliteConnectOptions::new()
+			.filename(path)
+			.journal_mode(SqliteJournalMode::Wal)
 			.create_if_missing(true);
 		let db = SqlitePool::connect_with(options).await?;
 
-		db.execute(include_str!("../../assets/setup_db.sql")).await?;
+		sqlx::migrate!().run(&db).await?;
 
-		Ok(Self {
+		let database = Self {
 			connection: db,
 			latest_timestamp: Arc::new(AtomicI64::new(0)),
-		})
+		};
+		// Served by the API right away, instead of only once something new was found
+		let latest = database.query_latest_timestamp().await?;
+		database.latest_timestamp.store(latest, Ordering::Release);
+		Ok(database)
+	}
+}
+
+/// Stores the bits of unsigned values such as hashes or Discord IDs, as SQLite only stores signed integers
+pub const fn to_sqlite_integer(value: u64) -> i64 {
+	value as i64
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::api::database::Database;
+	use crate::embed::EmbedData;
+	use crate::scrapers::scraper_resources::resources::ScrapeType;
+
+	#[tokio::test]
+	async fn test_urls_are_stored_once_per_source() {
+		let path = std::env::temp_dir().join(format!("wt_event_handler_{}.sqlite", std::process::id()));
+		let db = Database::new(path.to_str().unwrap()).await.unwrap();
+
+		db.store_recent(["https://example.com/a", "https://example.com/a"], 0).await.unwrap();
+		db.store_recent(["https://example.com/a"], 1).await.unwrap();
+		let stored: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM sources").fetch_one(&db.connection).await.unwrap();
+		assert_eq!(stored, 2);
+
+		// Reopening keeps the history
+		drop(db);
+		let db = Database::new(path.to_str().unwrap()).await.unwrap();
+		assert_ne!(db.get_latest_timestamp(), 0);
+		drop(std::fs::remove_file(path));
+	}
+
+	#[tokio::test]
+	async fn test_search() {
+		let path = std::env::temp_dir().join(format!("wt_event_handler_search_{}.sqlite", std::process::id()));
+		let db = Database::new(path.to_str().unwrap()).await.unwrap();
+
+		let articles = [
+			(0, "https://example.com/leopard", "Development: Leopard 2A7V", "The Leopard 2A7V joins the German tree"),
+			(0, "https://example.com/t-80", "Development: T-80BVM", "Reactive armor, and a Leopard mention"),
+			(1, "https://example.com/changelog", "Update 2.21", "Fixed the Leopard 2A7V turret"),
+		];
+		for (source, url, title, body) in articles {
+			db.store_recent([url], source).await.unwrap();
+			let mut embed = EmbedData::new(title, url, "", "", ScrapeType::Main);
+			embed.body = body.to_owned();
+			db.store_article(&embed, source, 0).await.unwrap();
+		}
+
+		let (total, hits) = db.search("leopard", None, 0, 10, 0).await.unwrap();
+		assert_eq!(total, 3);
+		// Title matches rank first
+		assert_eq!(hits[0].url, "https://example.com/leopard");
+
+		let (total, hits) = db.search("Leopard 2A7V", Some(1), 0, 10, 0).await.unwrap();
+		assert_eq!(total, 1);
+		assert!(hits[0].snippet.contains("**Leopard** **2A7V**"));
+
+		// Query syntax is not interpreted
+		assert_eq!(db.search("T-80BVM\" OR", None, 0, 10, 0).await.unwrap().0, 0);
+		assert_eq!(db.search("leopard", None, i64::MAX, 10, 0).await.unwrap().0, 0);
+		drop(std::fs::remove_file(path));
 	}
 }
\ No newline at end of file
@@ -1,16 +1,92 @@
 use std::sync::atomic::Ordering;
 
+use serde::Serialize;
 use sqlx::{Executor, query, Row};
 
-use crate::api::database::Database;
+use crate::api::database::{Database, to_sqlite_integer};
 use crate::api::db_error::DatabaseError;
+use crate::embed::EmbedData;
+use crate::scrapers::events::EventDates;
+use crate::scrapers::versions::compare_versions;
+
+#[derive(Debug, Clone)]
+/// Article content as it was when last checked, empty for articles never checked
+pub struct StoredContent {
+	pub url: String,
+	pub fingerprint: Option<i64>,
+	pub title: Option<String>,
+	pub preview: Option<String>,
+	pub body: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+/// An article as kept in the archive, content fields are empty until it was fetched once
+pub struct ArchivedArticle {
+	/// Increases with every article, pass the lowest one as `before` to get the next page
+	pub id: i64,
+	pub url: String,
+	pub source: u8,
+	pub fetch_date: i64,
+	pub title: Option<String>,
+	pub preview: Option<String>,
+	pub image: Option<String>,
+	pub scrape_type: Option<String>,
+	pub publish_date: Option<i64>,
+	pub body: Option<String>,
+	pub removed_date: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+/// An article matching a search, with the matching part of its text
+pub struct SearchHit {
+	pub id: i64,
+	pub url: String,
+	pub source: u8,
+	pub fetch_date: i64,
+	pub title: Option<String>,
+	pub preview: Option<String>,
+	pub image: Option<String>,
+	pub scrape_type: Option<String>,
+	pub publish_date: Option<i64>,
+	pub removed_date: Option<i64>,
+	/// Excerpt around the match, matched terms are in bold markdown
+	pub snippet: String,
+	/// Lower is a better match
+	pub rank: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+/// A client version as announced by a changelog
+pub struct GameVersion {
+	pub version: String,
+	/// False for hotfixes of an already known major.minor version
+	pub major: bool,
+	pub release_date: Option<i64>,
+	/// Changelog announcing the version
+	pub url: String,
+	pub seen_date: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+/// An event announced by a news article
+pub struct Event {
+	pub id: i64,
+	pub source: u8,
+	/// Article announcing the event
+	pub url: String,
+	pub title: String,
+	pub start_date: i64,
+	pub end_date: Option<i64>,
+	/// No time of day is known, the dates are midnight UTC
+	pub all_day: bool,
+}
 
 impl Database {
 	pub async fn store_recent_single(&self, value: &str, source: u8) -> Result<(), DatabaseError>
 	{
 		let now = chrono::Utc::now().timestamp();
 		let q = query!(// language=SQL
-			"INSERT INTO sources (url, fetch_date, source)
+			"INSERT OR IGNORE INTO sources (url, fetch_date, source)
 			VALUES (?, ?, ?);",
 						value, now, source);
 		self.connection.execute(q).await?;
@@ -32,6 +108,246 @@ impl Database {
 		Ok(())
 	}
 
+	/// Every URL stored for the source, with the time it was first seen
+	pub async fn get_known_urls(&self, source_id: u8) -> Result<Vec<(String, i64)>, DatabaseError> {
+		let q = query!(// language=SQL
+			"SELECT url, fetch_date
+			FROM sources
+			WHERE source = ?", source_id);
+		Ok(self.connection.fetch_all(q).await?.into_iter().map(|x| (x.get(0), x.get(1))).collect())
+	}
+
+	/// Archives the current version of an article, later versions are compared against it
+	pub async fn store_article(&self, embed: &EmbedData, source: u8, fingerprint: i64) -> Result<(), DatabaseError> {
+		let now = chrono::Utc::now().timestamp();
+		let scrape_type = embed.scrape_type.name();
+		let q = query!(// language=SQL
+			"UPDATE sources
+			SET fingerprint = ?, title = ?, preview = ?, image = ?, scrape_type = ?, publish_date = ?, body = ?, checked_date = ?
+			WHERE source = ? AND url = ?",
+						fingerprint, embed.title, embed.preview_text, embed.img_url, scrape_type, embed.published, embed.body, now, source, embed.url);
+		self.connection.execute(q).await?;
+		Ok(())
+	}
+
+	/// Articles first seen after `seen_since` and not checked since `checked_before`, least recently checked first
+	pub async fn get_due_rechecks(&self, source: u8, seen_since: i64, checked_before: i64, limit: u32) -> Result<Vec<StoredContent>, DatabaseError> {
+		let q = query!(// language=SQL
+			"SELECT url, fingerprint, title, preview, body
+			FROM sources
+			WHERE source = ? AND fetch_date >= ? AND COALESCE(checked_date, 0) < ? AND removed_date IS NULL
+			ORDER BY COALESCE(checked_date, 0)
+			LIMIT ?", source, seen_since, checked_before, limit);
+		Ok(self.connection.fetch_all(q).await?.into_iter().map(|x| StoredContent {
+			url: x.get(0),
+			fingerprint: x.get(1),
+			title: x.get(2),
+			preview: x.get(3),
+			body: x.get(4),
+		}).collect())
+	}
+
+	pub async fn get_content(&self, url: &str, source: u8) -> Result<Option<StoredContent>, DatabaseError> {
+		let q = query!(// language=SQL
+			"SELECT url, fingerprint, title, preview, body
+			FROM sources
+			WHERE source = ? AND url = ?", source, url);
+		Ok(self.connection.fetch_optional(q).await?.map(|x| StoredContent {
+			url: x.get(0),
+			fingerprint: x.get(1),
+			title: x.get(2),
+			preview: x.get(3),
+			body: x.get(4),
+		}))
+	}
+
+	/// When the article was first seen, if it is stored
+	pub async fn get_fetch_date(&self, url: &str, source: u8) -> Result<Option<i64>, DatabaseError> {
+		let q = query!(// language=SQL
+			"SELECT fetch_date
+			FROM sources
+			WHERE source = ? AND url = ?", source, url);
+		Ok(self.connection.fetch_optional(q).await?.map(|x| x.get(0)))
+	}
+
+	/// Records that an article was taken down, returns false if that was already known
+	pub async fn mark_removed(&self, url: &str, source: u8) -> Result<bool, DatabaseError> {
+		let now = chrono::Utc::now().timestamp();
+		let q = query!(// language=SQL
+			"UPDATE sources
+			SET removed_date = ?
+			WHERE source = ? AND url = ? AND removed_date IS NULL", now, source, url);
+		Ok(self.connection.execute(q).await?.rows_affected() > 0)
+	}
+
+	pub async fn mark_checked(&self, url: &str, source: u8) -> Result<(), DatabaseError> {
+		let now = chrono::Utc::now().timestamp();
+		let q = query!(// language=SQL
+			"UPDATE sources
+			SET checked_date = ?
+			WHERE source = ? AND url = ?", now, source, url);
+		self.connection.execute(q).await?;
+		Ok(())
+	}
+
+	/// Archived articles of the source with an id below `before`, newest first
+	pub async fn get_archive(&self, source_id: u8, before: i64, limit: u32) -> Result<Vec<ArchivedArticle>, DatabaseError> {
+		let q = query!(// language=SQL
+			"SELECT id, url, source, fetch_date, title, preview, image, scrape_type, publish_date, body, removed_date
+			FROM sources
+			WHERE source = ? AND id < ?
+			ORDER BY id DESC
+			LIMIT ?", source_id, before, limit);
+		Ok(self.connection.fetch_all(q).await?.into_iter().map(|x| ArchivedArticle {
+			id: x.get(0),
+			url: x.get(1),
+			source: x.get(2),
+			fetch_date: x.get(3),
+			title: x.get(4),
+			preview: x.get(5),
+			image: x.get(6),
+			scrape_type: x.get(7),
+			publish_date: x.get(8),
+			body: x.get(9),
+			removed_date: x.get(10),
+		}).collect())
+	}
+
+	/// Articles which were fetched and are still available, optionally of one source, latest first and each URL once
+	pub async fn get_feed(&self, source: Option<u8>, limit: u32) -> Result<Vec<ArchivedArticle>, DatabaseError> {
+		let q = query!(// language=SQL
+			"SELECT id, url, source, fetch_date, title, preview, image, scrape_type, publish_date, body, removed_date
+			FROM sources
+			WHERE id IN (
+				SELECT MIN(id)
+				FROM sources
+				WHERE title IS NOT NULL AND removed_date IS NULL AND (? IS NULL OR source = ?)
+				GROUP BY url
+			)
+			ORDER BY COALESCE(publish_date, fetch_date) DESC
+			LIMIT ?", source, source, limit);
+		Ok(self.connection.fetch_all(q).await?.into_iter().map(|x| ArchivedArticle {
+			id: x.get(0),
+			url: x.get(1),
+			source: x.get(2),
+			fetch_date: x.get(3),
+			title: x.get(4),
+			preview: x.get(5),
+			image: x.get(6),
+			scrape_type: x.get(7),
+			publish_date: x.get(8),
+			body: x.get(9),
+			removed_date: x.get(10),
+		}).collect())
+	}
+
+	/// Ranked articles containing all words of the search, optionally of one source and published or first seen since a timestamp
+	pub async fn search(&self, search: &str, source: Option<u8>, since: i64, limit: u32, offset: u32) -> Result<(i64, Vec<SearchHit>), DatabaseError> {
+		let pattern = fts_pattern(search);
+
+		let q = query!(// language=SQL
+			"SELECT COUNT(*) AS total
+			FROM sources_search
+			JOIN sources ON sources.id = sources_search.rowid
+			WHERE sources_search MATCH ? AND (? IS NULL OR sources.source = ?) AND COALESCE(sources.publish_date, sources.fetch_date) >= ?",
+			pattern, source, source, since);
+		let total = self.connection.fetch_one(q).await?.get(0);
+
+		// Titles weigh most, then previews, then the body
+		let q = query!(// language=SQL
+			"SELECT sources.id, url, source, fetch_date, sources.title, sources.preview, image, scrape_type, publish_date, removed_date,
+				snippet(sources_search, -1, '**', '**', '…', 24) AS \"snippet: String\",
+				bm25(sources_search, 10.0, 4.0, 1.0) AS \"rank: f64\"
+			FROM sources_search
+			JOIN sources ON sources.id = sources_search.rowid
+			WHERE sources_search MATCH ? AND (? IS NULL OR sources.source = ?) AND COALESCE(sources.publish_date, sources.fetch_date) >= ?
+			ORDER BY rank
+			LIMIT ? OFFSET ?",
+			pattern, source, source, since, limit, offset);
+		let hits = self.connection.fetch_all(q).await?.into_iter().map(|x| SearchHit {
+			id: x.get(0),
+			url: x.get(1),
+			source: x.get(2),
+			fetch_date: x.get(3),
+			title: x.get(4),
+			preview: x.get(5),
+			image: x.get(6),
+			scrape_type: x.get(7),
+			publish_date: x.get(8),
+			removed_date: x.get(9),
+			snippet: x.get(10),
+			rank: x.get(11),
+		}).collect();
+		Ok((total, hits))
+	}
+
+	/// Adds a version to the history, returns false if it was already known
+	pub async fn store_version(&self, version: &GameVersion) -> Result<bool, DatabaseError> {
+		let q = query!(// language=SQL
+			"INSERT OR IGNORE INTO versions (version, major, release_date, url, seen_date)
+			VALUES (?, ?, ?, ?, ?)",
+						version.version, version.major, version.release_date, version.url, version.seen_date);
+		Ok(self.connection.execute(q).await?.rows_affected() > 0)
+	}
+
+	/// Every known version, the highest first
+	pub async fn get_versions(&self) -> Result<Vec<GameVersion>, DatabaseError> {
+		let q = query!(// language=SQL
+			"SELECT version, major, release_date, url, seen_date
+			FROM versions");
+		let mut versions: Vec<GameVersion> = self.connection.fetch_all(q).await?.into_iter().map(|x| GameVersion {
+			version: x.get(0),
+			major: x.get(1),
+			release_date: x.get(2),
+			url: x.get(3),
+			seen_date: x.get(4),
+		}).collect();
+		versions.sort_by(|a, b| compare_versions(&b.version, &a.version));
+		Ok(versions)
+	}
+
+	/// Stores the event of an article, replacing the dates found in an earlier version of it
+	pub async fn store_event(&self, source: u8, url: &str, title: &str, dates: &EventDates) -> Result<(), DatabaseError> {
+		let q = query!(// language=SQL
+			"INSERT INTO events (source, url, title, start_date, end_date, all_day)
+			VALUES (?, ?, ?, ?, ?, ?)
+			ON CONFLICT (source, url) DO UPDATE
+			SET title = excluded.title, start_date = excluded.start_date, end_date = excluded.end_date, all_day = excluded.all_day",
+						source, url, title, dates.start, dates.end, dates.all_day);
+		self.connection.execute(q).await?;
+		Ok(())
+	}
+
+	/// Events ending after `since`, or starting after it when their end is unknown, unless their article was taken down
+	pub async fn get_events(&self, since: i64) -> Result<Vec<Event>, DatabaseError> {
+		let q = query!(// language=SQL
+			"SELECT id, source, url, title, start_date, end_date, all_day
+			FROM events
+			WHERE COALESCE(end_date, start_date) >= ?
+				AND NOT EXISTS (SELECT 1 FROM sources WHERE sources.source = events.source AND sources.url = events.url AND removed_date IS NOT NULL)
+			ORDER BY start_date", since);
+		Ok(self.connection.fetch_all(q).await?.into_iter().map(|x| Event {
+			id: x.get(0),
+			source: x.get(1),
+			url: x.get(2),
+			title: x.get(3),
+			start_date: x.get(4),
+			end_date: x.get(5),
+			all_day: x.get(6),
+		}).collect())
+	}
+
+	/// Records a reminder as sent, returns false if it already was
+	pub async fn claim_reminder(&self, event: i64, hook: u64, kind: &str, lead: i64, due: i64) -> Result<bool, DatabaseError> {
+		let now = chrono::Utc::now().timestamp();
+		let hook = to_sqlite_integer(hook);
+		let q = query!(// language=SQL
+			"INSERT OR IGNORE INTO sent_reminders (event, hook, kind, lead, due_date, sent_date)
+			VALUES (?, ?, ?, ?, ?, ?)",
+						event, hook, kind, lead, due, now);
+		Ok(self.connection.execute(q).await?.rows_affected() > 0)
+	}
+
 	pub async fn get_latest_news_from_source(&self, source_id: u8) -> Result<String, DatabaseError> {
 		let q = query!(// language=SQL
 			"SELECT url
@@ -54,12 +370,19 @@ impl Database {
 		self.latest_timestamp.load(Ordering::Relaxed)
 	}
 
-	async fn query_latest_timestamp(&self) -> Result<i64, DatabaseError> {
+	pub(crate) async fn query_latest_timestamp(&self) -> Result<i64, DatabaseError> {
 		let q = query!(// language=SQL
-			"SELECT fetch_date
-			 FROM sources
-			 ORDER BY fetch_date DESC ");
+			"SELECT COALESCE(MAX(fetch_date), 0) AS latest
+			 FROM sources");
 		let res = self.connection.fetch_one(q).await?;
 		Ok(res.get(0))
 	}
+}
+
+/// Quotes every word, so that user input is never read as FTS5 query syntax
+fn fts_pattern(search: &str) -> String {
+	search.split_whitespace()
+		.map(|word| format!("\"{}\"", word.replace('"', "\"\"")))
+		.collect::<Vec<_>>()
+		.join(" ")
 }
\ No newline at end of file
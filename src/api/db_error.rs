@@ -7,4 +7,7 @@ pub enum DatabaseError {
 
 	#[error(transparent)]
 	MigrateError(#[from] sqlx::migrate::MigrateError),
+
+	#[error(transparent)]
+	Io(#[from] std::io::Error),
 }
\ No newline at end of file
@@ -2,32 +2,171 @@
 
 use std::process::exit;
 
-use actix_web::{get, post, Responder, web};
-use actix_web::error::{ErrorForbidden, ErrorGone};
+use actix_web::{get, HttpRequest, HttpResponse, post, Responder, web};
+use actix_web::error::{ErrorBadRequest, ErrorForbidden, ErrorGone, ErrorNotFound};
 use serde::{Deserialize, Serialize};
 
-use crate::{BOOT_TIME, NewsError, SHUTDOWN_KEY};
+use crate::{BOOT_TIME, SHUTDOWN_KEY};
 use crate::api::database::Database;
+use crate::api::calendar::calendar;
+use crate::api::database_queries::{ArchivedArticle, SearchHit};
+use crate::api::feed::{atom, feed_id};
 use crate::api::error::ApiError;
 use crate::error::ship_error_webhook;
 use crate::json::sources::Sources;
 use crate::scrapers::html_processing::get_embed_data;
-use crate::scrapers::scraper_resources::resources::ScrapeType;
+use crate::scrapers::news_source::SourceRegistry;
+use crate::scrapers::scraper_resources::dates::parse_date;
 
 #[get("/news/latest/{source}")]
-pub async fn greet(source: web::Path<String>, db: web::Data<Database>) -> impl Responder {
-	db.get_latest_news_from_source(Sources::id_from_name(&source)).await.unwrap()
+pub async fn greet(source: web::Path<String>, db: web::Data<Database>, sources: web::Data<Sources>) -> impl Responder {
+	if let Some(id) = sources.id_from_name(&source) {
+		Ok(db.get_latest_news_from_source(id).await.unwrap())
+	} else {
+		Err(ErrorNotFound(format!("Unknown source '{source}'")))
+	}
 }
 
 #[get("/news/latest")]
-pub async fn get_latest_news(db: web::Data<Database>) -> impl Responder {
+pub async fn get_latest_news(db: web::Data<Database>, sources: web::Data<Sources>) -> impl Responder {
 	let mut total = vec![];
-	for source in Sources::new().sources {
+	for source in sources.sources.iter().filter(|source| source.enabled) {
 		total.push(db.get_latest_news_from_source(source.id).await.unwrap());
 	}
 	serde_json::to_string(&total).unwrap()
 }
 
+#[derive(Deserialize)]
+pub struct ArchiveQuery {
+	pub before: Option<i64>,
+	pub limit: Option<u32>,
+}
+
+// Articles returned per page of the archive or search when no limit is requested, and at most
+const ARCHIVE_PAGE: u32 = 20;
+const ARCHIVE_PAGE_MAX: u32 = 100;
+
+#[get("/news/archive/{source}")]
+pub async fn get_archive(source: web::Path<String>, query: web::Query<ArchiveQuery>, db: web::Data<Database>, sources: web::Data<Sources>) -> impl Responder {
+	let Some(id) = sources.id_from_name(&source) else {
+		return Err(ErrorNotFound(format!("Unknown source '{source}'")));
+	};
+	let limit = query.limit.unwrap_or(ARCHIVE_PAGE).min(ARCHIVE_PAGE_MAX);
+	let articles = db.get_archive(id, query.before.unwrap_or(i64::MAX), limit).await.map_err(ApiError::from)?;
+	Ok(web::Json(articles))
+}
+
+// Latest articles a feed contains
+const FEED_ENTRIES: u32 = 50;
+
+fn atom_response(title: &str, source: Option<u8>, req: &HttpRequest, articles: &[ArchivedArticle]) -> HttpResponse {
+	let info = req.connection_info();
+	let self_url = format!("{}://{}{}", info.scheme(), info.host(), req.uri());
+	HttpResponse::Ok()
+		.content_type("application/atom+xml; charset=utf-8")
+		.body(atom(title, &feed_id(source), &self_url, articles))
+}
+
+#[get("/feed.atom")]
+pub async fn get_feed(req: HttpRequest, db: web::Data<Database>) -> impl Responder {
+	let articles = db.get_feed(None, FEED_ENTRIES).await.map_err(ApiError::from)?;
+	Ok::<_, actix_web::Error>(atom_response("War Thunder news", None, &req, &articles))
+}
+
+#[get("/feed/{source}.atom")]
+pub async fn get_source_feed(source: web::Path<String>, req: HttpRequest, db: web::Data<Database>, sources: web::Data<Sources>) -> impl Responder {
+	let Some(id) = sources.id_from_name(&source) else {
+		return Err(ErrorNotFound(format!("Unknown source '{source}'")));
+	};
+	let articles = db.get_feed(Some(id), FEED_ENTRIES).await.map_err(ApiError::from)?;
+	Ok(atom_response(&format!("War Thunder news: {source}"), Some(id), &req, &articles))
+}
+
+#[derive(Deserialize)]
+pub struct SearchQuery {
+	pub q: String,
+	pub source: Option<String>,
+	/// Unix timestamp or date such as "2022-10-16"
+	pub since: Option<String>,
+	pub page: Option<u32>,
+	pub limit: Option<u32>,
+}
+
+#[derive(Serialize)]
+pub struct SearchResults {
+	pub total: i64,
+	pub page: u32,
+	pub results: Vec<SearchHit>,
+}
+
+#[get("/news/search")]
+pub async fn search_news(query: web::Query<SearchQuery>, db: web::Data<Database>, sources: web::Data<Sources>) -> impl Responder {
+	if query.q.trim().is_empty() {
+		return Err(ErrorBadRequest("The search 'q' is empty"));
+	}
+	let source = match &query.source {
+		Some(name) => Some(sources.id_from_name(name).ok_or(ErrorNotFound(format!("Unknown source '{name}'")))?),
+		None => None,
+	};
+	let since = match &query.since {
+		Some(since) => since.parse().ok().or(parse_date(since)).ok_or(ErrorBadRequest(format!("'{since}' is no timestamp or date")))?,
+		None => 0,
+	};
+
+	let page = query.page.unwrap_or(1).max(1);
+	let limit = query.limit.unwrap_or(ARCHIVE_PAGE).min(ARCHIVE_PAGE_MAX);
+	let (total, results) = db.search(&query.q, source, since, limit, (page - 1).saturating_mul(limit)).await.map_err(ApiError::from)?;
+	Ok(web::Json(SearchResults {
+		total,
+		page,
+		results,
+	}))
+}
+
+#[get("/game/version")]
+pub async fn get_game_version(db: web::Data<Database>) -> impl Responder {
+	let versions = db.get_versions().await.map_err(ApiError::from)?;
+	versions.into_iter().next()
+		.map(web::Json)
+		.ok_or(ErrorNotFound("No version is known yet"))
+}
+
+#[get("/game/versions")]
+pub async fn get_game_versions(db: web::Data<Database>) -> impl Responder {
+	let versions = db.get_versions().await.map_err(ApiError::from)?;
+	Ok::<_, actix_web::Error>(web::Json(versions))
+}
+
+#[derive(Deserialize)]
+pub struct EventQuery {
+	/// Unix timestamp or date such as "2022-10-16"
+	pub since: Option<String>,
+}
+
+// Seconds past events are still served for
+const EVENT_HISTORY: i64 = 60 * 60 * 24 * 30;
+
+fn events_since(query: &EventQuery) -> Result<i64, actix_web::Error> {
+	match &query.since {
+		Some(since) => since.parse().ok().or(parse_date(since)).ok_or(ErrorBadRequest(format!("'{since}' is no timestamp or date"))),
+		None => Ok(chrono::Utc::now().timestamp() - EVENT_HISTORY),
+	}
+}
+
+#[get("/events")]
+pub async fn get_events(query: web::Query<EventQuery>, db: web::Data<Database>) -> impl Responder {
+	let events = db.get_events(events_since(&query)?).await.map_err(ApiError::from)?;
+	Ok::<_, actix_web::Error>(web::Json(events))
+}
+
+#[get("/events.ics")]
+pub async fn get_events_calendar(query: web::Query<EventQuery>, db: web::Data<Database>) -> impl Responder {
+	let events = db.get_events(events_since(&query)?).await.map_err(ApiError::from)?;
+	Ok::<_, actix_web::Error>(HttpResponse::Ok()
+		.content_type("text/calendar; charset=utf-8")
+		.body(calendar(&events, chrono::Utc::now().timestamp())))
+}
+
 #[get("/settings/shutdown/{key}")]
 pub async fn shutdown(key: web::Path<String>) -> impl Responder {
 	if *key == *SHUTDOWN_KEY {
@@ -58,9 +197,12 @@ pub struct ManualPost {
 }
 
 #[post("/news/post")]
-pub async fn post_manual(post: web::Json<ManualPost>) -> impl Responder {
-	let scrape_type = ScrapeType::infer_from_url(&post.url);
-	let embed = get_embed_data(&post.url, scrape_type).await?;
-	embed.handle_webhooks(true, scrape_type).await;
-	Ok::<&str, ApiError>("")
+pub async fn post_manual(post: web::Json<ManualPost>, sources: web::Data<Sources>, registry: web::Data<SourceRegistry>) -> impl Responder {
+	let Some(source) = sources.source_for_url(&post.url) else {
+		return Err(ErrorNotFound(format!("No source is defined for {}", post.url)));
+	};
+	let news_source = registry.resolve(source).map_err(ApiError::from)?;
+	let embed = get_embed_data(&post.url, source, &registry).await.map_err(ApiError::from)?;
+	embed.handle_webhooks(true, news_source, source).await;
+	Ok("")
 }
\ No newline at end of file
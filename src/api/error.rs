@@ -1,12 +1,16 @@
-use std::fmt::{Debug, Display};
+use std::fmt::Debug;
 use actix_web::ResponseError;
 use thiserror::Error as ThisError;
+use crate::api::db_error::DatabaseError;
 use crate::NewsError;
 
 #[derive(Debug, ThisError)]
 pub enum ApiError {
 	#[error(transparent)]
 	InternalServerError(#[from] NewsError),
+
+	#[error(transparent)]
+	Database(#[from] DatabaseError),
 }
 
 
@@ -0,0 +1,95 @@
+use chrono::{DateTime, SecondsFormat};
+
+use crate::api::database_queries::ArchivedArticle;
+use crate::embed::EMPTY_IMG;
+
+// Prefix of the tag URIs identifying feeds, unlike the URL they do not depend on the hostname serving them
+const FEED_TAG: &str = "tag:wt_event_handler,2022:feed";
+
+/// Stable id of the feed of a source, or of the combined feed without one
+pub fn feed_id(source: Option<u8>) -> String {
+	source.map_or_else(|| FEED_TAG.to_owned(), |source| format!("{FEED_TAG}:{source}"))
+}
+
+/// Renders articles as Atom feed, `self_url` is where the feed itself is served
+pub fn atom(title: &str, id: &str, self_url: &str, articles: &[ArchivedArticle]) -> String {
+	let date = |article: &ArchivedArticle| article.publish_date.unwrap_or(article.fetch_date);
+	let updated = articles.iter().map(date).max().unwrap_or_default();
+
+	let mut feed = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<feed xmlns=\"http://www.w3.org/2005/Atom\">\n");
+	feed.push_str(&format!("\t<id>{}</id>\n", escape(id)));
+	feed.push_str(&format!("\t<title>{}</title>\n", escape(title)));
+	feed.push_str(&format!("\t<updated>{}</updated>\n", rfc3339(updated)));
+	feed.push_str(&format!("\t<link rel=\"self\" href=\"{}\"/>\n", escape(self_url)));
+	feed.push_str("\t<author><name>Gaijin Entertainment</name></author>\n");
+	feed.push_str("\t<generator uri=\"https://github.com/Warthunder-Open-Source-Foundation/wt_event_handler\">wt_event_handler</generator>\n");
+
+	for article in articles {
+		let url = escape(&article.url);
+		feed.push_str("\t<entry>\n");
+		feed.push_str(&format!("\t\t<id>{url}</id>\n"));
+		feed.push_str(&format!("\t\t<title>{}</title>\n", escape(article.title.as_deref().unwrap_or(&article.url))));
+		feed.push_str(&format!("\t\t<link rel=\"alternate\" href=\"{url}\"/>\n"));
+		feed.push_str(&format!("\t\t<updated>{}</updated>\n", rfc3339(date(article))));
+		if let Some(published) = article.publish_date {
+			feed.push_str(&format!("\t\t<published>{}</published>\n", rfc3339(published)));
+		}
+		if let Some(scrape_type) = &article.scrape_type {
+			feed.push_str(&format!("\t\t<category term=\"{}\"/>\n", escape(scrape_type)));
+		}
+		if let Some(image) = article.image.as_deref().filter(|image| *image != EMPTY_IMG) {
+			feed.push_str(&format!("\t\t<link rel=\"enclosure\" href=\"{}\"/>\n", escape(image)));
+		}
+		if let Some(preview) = article.preview.as_deref().filter(|preview| !preview.is_empty()) {
+			feed.push_str(&format!("\t\t<summary>{}</summary>\n", escape(preview)));
+		}
+		if let Some(body) = article.body.as_deref().filter(|body| !body.is_empty()) {
+			feed.push_str(&format!("\t\t<content type=\"text\">{}</content>\n", escape(body)));
+		}
+		feed.push_str("\t</entry>\n");
+	}
+	feed.push_str("</feed>\n");
+	feed
+}
+
+fn rfc3339(timestamp: i64) -> String {
+	DateTime::from_timestamp(timestamp, 0).unwrap_or_default().to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+fn escape(text: &str) -> String {
+	text.replace('&', "&amp;")
+		.replace('<', "&lt;")
+		.replace('>', "&gt;")
+		.replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::api::database_queries::ArchivedArticle;
+	use crate::api::feed::{atom, feed_id};
+
+	#[test]
+	fn test_atom() {
+		let articles = [ArchivedArticle {
+			id: 1,
+			url: "https://warthunder.com/en/news/7640-event-the-battle-for-arachis-en".to_owned(),
+			source: 0,
+			fetch_date: 1_665_403_200,
+			title: Some("Tanks & <planes>".to_owned()),
+			preview: Some("The battle begins".to_owned()),
+			image: None,
+			scrape_type: Some("Main".to_owned()),
+			publish_date: None,
+			body: None,
+			removed_date: None,
+		}];
+		let feed = atom("warthunder_news", &feed_id(Some(0)), "http://localhost/feed/warthunder_news.atom", &articles);
+		assert!(feed.contains("\t<id>tag:wt_event_handler,2022:feed:0</id>\n"));
+		assert!(feed.contains("\t<link rel=\"self\" href=\"http://localhost/feed/warthunder_news.atom\"/>\n"));
+		assert_eq!(feed_id(None), "tag:wt_event_handler,2022:feed");
+		assert!(feed.contains("\t<updated>2022-10-10T12:00:00Z</updated>\n"));
+		assert!(feed.contains("\t\t<title>Tanks &amp; &lt;planes&gt;</title>\n"));
+		assert!(!feed.contains("<content"));
+		assert!(feed.ends_with("</entry>\n</feed>\n"));
+	}
+}
\ No newline at end of file
@@ -2,4 +2,6 @@ pub mod endpoints;
 pub mod database;
 pub mod db_error;
 pub mod database_queries;
-pub mod error;
\ No newline at end of file
+pub mod error;
+pub mod calendar;
+pub mod feed;
\ No newline at end of file
@@ -1,11 +1,40 @@
 use crate::fetch_loop::STATS;
+use crate::json::sources::Source;
+use crate::json::webhooks::Notifications;
+use crate::scrapers::news_source::NewsSource;
 use crate::scrapers::scraper_resources::resources::ScrapeType;
 use crate::statistics::Incr;
 use crate::WEBHOOK_AUTH;
-use crate::webhook_handler::{deliver_webhook, match_filter};
+use crate::webhook_handler::{deliver_notice, deliver_webhook};
 
 pub const EMPTY_IMG: &str = "https://raw.githubusercontent.com/Warthunder-Open-Source-Foundation/wt_event_handler/master/assets/empty.png";
 
+pub const DEFAULT_LANGUAGE: &str = "en";
+
+const FAIL_OVER_TEXT: &str = "Failed to collect embed data";
+
+// Summaries from feeds or APIs can be entire articles, embeds only need the beginning
+pub const PREVIEW_LENGTH: usize = 500;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+/// Who wrote an article, as shown in the author field of its embed
+pub struct Author {
+	pub name: String,
+	pub avatar: Option<String>,
+	/// Staff badge or rank, such as "Developer"
+	pub badge: Option<String>,
+}
+
+impl Author {
+	/// Such as "Stona_WT (Developer)"
+	pub fn display_name(&self) -> String {
+		match &self.badge {
+			Some(badge) => format!("{} ({badge})", self.name),
+			None => self.name.clone(),
+		}
+	}
+}
+
 #[derive(Clone, Debug)]
 pub struct EmbedData {
 	pub scrape_type: ScrapeType,
@@ -13,13 +42,26 @@ pub struct EmbedData {
 	pub url: String,
 	pub img_url: String,
 	pub preview_text: String,
+	/// Language of the source the article was found on
+	pub language: String,
+	/// Plain text of the entire article, empty when the source offers none
+	pub body: String,
+	/// When the article was published as UTC timestamp, if the source states it
+	pub published: Option<i64>,
+	/// Client version announced by a changelog
+	pub version: Option<String>,
+	pub author: Option<Author>,
 }
 
 impl EmbedData {
-	pub async fn handle_webhooks(&self, is_filtered: bool, scrape_type: ScrapeType) {
+	/// Delivers the article to every webhook of its language, filtered by the kind of the source it was found on
+	pub async fn handle_webhooks(&self, is_filtered: bool, news_source: &dyn NewsSource, source: &Source) {
 		for (i, hook) in WEBHOOK_AUTH.hooks.iter().enumerate() {
+			if !hook.reads(&self.language) {
+				continue;
+			}
 			if is_filtered {
-				if match_filter(&self.url, hook, scrape_type) {
+				if news_source.matches_filter(source, &self.url, hook) {
 					deliver_webhook(self.clone(), i).await;
 				}
 			} else {
@@ -28,6 +70,26 @@ impl EmbedData {
 			STATS.lock().await.increment(Incr::PostCounter);
 		}
 	}
+	/// Tells every webhook which received the article and opted into the notice, such as an edit of it
+	pub async fn handle_notice(&self, label: &str, notice: &str, news_source: &dyn NewsSource, source: &Source, opted_in: fn(&Notifications) -> bool) {
+		for (i, hook) in WEBHOOK_AUTH.hooks.iter().enumerate() {
+			if !opted_in(&hook.notify) || !hook.reads(&self.language) || !news_source.matches_filter(source, &self.url, hook) {
+				continue;
+			}
+			deliver_notice(self, label, notice, i).await;
+			STATS.lock().await.increment(Incr::PostCounter);
+		}
+	}
+	/// Tells every webhook which opted into the announcement, regardless of its filters and languages
+	pub async fn handle_announcement(&self, label: &str, notice: &str, opted_in: fn(&Notifications) -> bool) {
+		for (i, hook) in WEBHOOK_AUTH.hooks.iter().enumerate() {
+			if !opted_in(&hook.notify) {
+				continue;
+			}
+			deliver_notice(self, label, notice, i).await;
+			STATS.lock().await.increment(Incr::PostCounter);
+		}
+	}
 	pub fn new(title: &str, url: &str, img_url: &str, preview_text: &str, scrape_type: ScrapeType) -> Self {
 		let sanitized_img_url = img_url.replace(' ', "%20");
 		Self {
@@ -36,6 +98,11 @@ impl EmbedData {
 			url: url.to_owned(),
 			img_url: sanitized_img_url,
 			preview_text: preview_text.to_owned(),
+			language: DEFAULT_LANGUAGE.to_owned(),
+			body: String::new(),
+			published: None,
+			version: None,
+			author: None,
 		}
 	}
 	pub fn test() -> Self {
@@ -45,15 +112,28 @@ impl EmbedData {
 			url: "https://github.com/Warthunder-Open-Source-Foundation/wt_event_handler".to_owned(),
 			img_url: "https://avatars.githubusercontent.com/u/97326911?s=200&v=4".to_owned(),
 			preview_text: "Test preview text".to_owned(),
+			language: DEFAULT_LANGUAGE.to_owned(),
+			body: String::new(),
+			published: None,
+			version: None,
+			author: None,
 		}
 	}
 	pub fn fail_over(url: &str, scrape_type: ScrapeType) -> Self {
 		Self {
 			scrape_type,
-			title: "Failed to collect embed data".to_string(),
+			title: FAIL_OVER_TEXT.to_string(),
 			url: url.to_string(),
 			img_url: EMPTY_IMG.to_string(),
-			preview_text: "Failed to collect embed data".to_string(),
+			preview_text: FAIL_OVER_TEXT.to_string(),
+			language: DEFAULT_LANGUAGE.to_string(),
+			body: String::new(),
+			published: None,
+			version: None,
+			author: None,
 		}
 	}
+	pub fn is_fail_over(&self) -> bool {
+		self.title == FAIL_OVER_TEXT && self.preview_text == FAIL_OVER_TEXT
+	}
 }
\ No newline at end of file
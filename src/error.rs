@@ -37,6 +37,18 @@ pub enum NewsError {
 	#[error("SelectedNothing: Selector: \'{0}\' found no item.\nDocument: {1}")]
 	SelectedNothing(String, String),
 
+	/// URL disallowed by the robots.txt of its domain
+	#[error("RobotsDisallowed: The robots.txt of \'{0}\' does not permit polling it")]
+	RobotsDisallowed(String),
+
+	/// URL of a document which was taken down, the server answered with 404 or 410
+	#[error("Gone: '{0}' was taken down")]
+	Gone(String),
+
+	/// Reason why the source definitions were rejected
+	#[error("BadSourceConfig: The source definitions are invalid: {0}")]
+	BadSourceConfig(String),
+
 	#[error(transparent)]
 	SerenityError(#[from] serenity::Error),
 
@@ -48,6 +60,9 @@ pub enum NewsError {
 
 	#[error(transparent)]
 	IOError(#[from] std::io::Error),
+
+	#[error(transparent)]
+	FeedParse(#[from] feed_rs::parser::ParseFeedError),
 }
 
 // Extra text is not an option thanks to type-system fuckery not permitting the type Option contain a impl statement
@@ -1,5 +1,7 @@
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::process::exit;
+use std::sync::Arc;
 use std::time::Duration;
 
 #[cfg(feature = "api")]
@@ -7,19 +9,27 @@ use actix_cors::Cors;
 use actix_web::{App, HttpServer};
 use actix_web::web::Data;
 use lazy_static::lazy_static;
-use tokio::sync::Mutex;
+use tokio::sync::{mpsc, Mutex, Semaphore};
 use tracing::{error, info, warn};
 
+use crate::CONFIG;
 use crate::api::database::Database;
-use crate::api::endpoints::{get_latest_news, get_latest_timestamp, get_uptime, greet, post_manual, shutdown};
+use crate::api::endpoints::{get_archive, get_events, get_events_calendar, get_feed, get_game_version, get_game_versions, get_latest_news, get_latest_timestamp, get_source_feed, get_uptime, greet, post_manual, search_news, shutdown};
 use crate::error::{error_webhook, NewsError};
-use crate::json::sources::Sources;
-use crate::scrapers::html_processing::html_processor;
+use crate::json::sources::{Source, Sources};
+use crate::scrapers::edits::{recheck_articles, remember};
+use crate::scrapers::html_processing::{html_processor, scrape_links};
+use crate::scrapers::news_source::SourceRegistry;
+use crate::reminders::remind;
+use crate::scheduler::Scheduler;
 use crate::scrapers::scraper_resources::resources::ScrapeType;
+use crate::scrapers::versions::record_version;
+use crate::scrapers::withdrawals::check_unlisted;
 use crate::statistics::{Incr, increment, Statistics};
 use crate::timeout::Timeout;
 
-const FETCH_DELAY: u64 = 40;
+// Amount of sources fetched at the same time
+const SOURCE_WORKERS: usize = 4;
 
 pub const STAT_COOLDOWN_HOURS: u64 = 24;
 // in seconds
@@ -30,15 +40,20 @@ lazy_static! {
 	pub static ref STATS: Mutex<Statistics> = Mutex::new(Statistics::new());
 }
 
-pub async fn fetch_loop(hooks: bool) {
-	let database = Database::new().await.expect("Cannot initiate DB");
-	let mut sources = Sources::build(&database).await.expect("I fucked up my soup");
+pub async fn fetch_loop(hooks: bool, registry: SourceRegistry) {
+	let database = Database::new(&CONFIG.database.path).await.expect("Cannot initiate DB");
+	let mut timeouts = Timeout::new();
+	let mut sources = match Sources::build(&database, &registry, &mut timeouts).await {
+		Ok(sources) => sources,
+		Err(e) => {
+			error!("{e}");
+			panic!("{e}");
+		}
+	};
 
 	#[cfg(debug_assertions)]
 	sources.debug_remove_tracked_urls::<&[&str]>(&[]);
 
-	let mut timeouts = Timeout::new();
-
 	// Spawn statistics thread
 	tokio::task::spawn(async {
 		warn!("Spawned logging thread");
@@ -50,10 +65,17 @@ pub async fn fetch_loop(hooks: bool) {
 		}
 	});
 
+	// Spawn reminder thread
+	if hooks {
+		tokio::task::spawn(remind(database.clone(), sources.sources.clone(), registry.clone()));
+	}
+
 	// Spawn API thread
 	#[cfg(feature = "api")]
 	tokio::task::spawn({
 		let cloned_database = Data::new(database.clone());
+		let cloned_sources = Data::new(sources.clone());
+		let cloned_registry = Data::new(registry.clone());
 		info!("Spawned API thread");
 		HttpServer::new(move || {
 			let cors = Cors::default()
@@ -63,12 +85,22 @@ pub async fn fetch_loop(hooks: bool) {
 			App::new()
 				.wrap(cors)
 				.app_data(Data::clone(&cloned_database))
+				.app_data(Data::clone(&cloned_sources))
+				.app_data(Data::clone(&cloned_registry))
 				.service(greet)
 				.service(get_latest_news)
 				.service(shutdown)
 				.service(get_latest_timestamp)
 				.service(get_uptime)
 				.service(post_manual)
+				.service(get_archive)
+				.service(search_news)
+				.service(get_game_version)
+				.service(get_game_versions)
+				.service(get_events)
+				.service(get_events_calendar)
+				.service(get_feed)
+				.service(get_source_feed)
 		})
 			.bind(("0.0.0.0", 8082))
 			.expect("Cant bind local host on port 8080")
@@ -91,48 +123,153 @@ pub async fn fetch_loop(hooks: bool) {
 	// 	exit(0);
 	// });
 
-	loop {
-		for source in &mut sources.sources {
-			if !timeouts.is_timed_out(&source.name) {
-				increment(Incr::FetchCounter).await;
-				match html_processor(source).await {
-					Ok(news) => {
-						for news_embed in &news {
-							if hooks {
-								news_embed.handle_webhooks(true, source.scrape_type).await;
-							}
-							increment(Incr::NewNews).await;
-						}
-
-						source.store_recent(news.iter().map(|new| &new.url));
-						let _db_insert_result = database.store_recent(news.iter().map(|new| &new.url), source.id).await;
-					}
-					Err(e) => {
-						increment(Incr::Errors).await;
-						handle_err(e, source.scrape_type, source.name.clone(), &mut timeouts, hooks).await;
-					}
-				}
+	let mut scheduler = Scheduler::new(&sources.sources);
+	let enabled_count = sources.sources.iter().filter(|source| source.enabled).count();
+	let sources: HashMap<u8, Arc<Mutex<Source>>> = sources.sources.into_iter()
+		.map(|source| (source.id, Arc::new(Mutex::new(source))))
+		.collect();
+
+	let registry = Arc::new(registry);
+	let timeouts = Arc::new(Mutex::new(timeouts));
+	let workers = Arc::new(Semaphore::new(SOURCE_WORKERS));
+	let mut fetched_once = HashSet::new();
+	let mut running = vec![];
+	// Workers run as their own tasks, so unrecoverable errors have to be brought back to stop the bot
+	let (fatal_sender, mut fatal_receiver) = mpsc::unbounded_channel::<NewsError>();
+
+	while let Some((id, due)) = scheduler.next_due() {
+		tokio::select! {
+			() = tokio::time::sleep_until(due) => {}
+			Some(e) = fatal_receiver.recv() => {
+				panic!("{:?}", e);
 			}
-			info!("Waiting for {FETCH_DELAY} seconds");
-			tokio::time::sleep(Duration::from_secs(FETCH_DELAY)).await;
 		}
 
+		// A source which is still being fetched from its last turn simply skips this one, without waiting for it
+		let Ok(source) = Arc::clone(&sources[&id]).try_lock_owned() else {
+			scheduler.reschedule(id);
+			continue;
+		};
+		if !source.enabled {
+			scheduler.remove(id);
+			continue;
+		}
+		scheduler.update(&source);
+		scheduler.reschedule(id);
+		info!("Fetching {} again in {} seconds", source.name, source.interval);
+
+		let permit = Arc::clone(&workers).acquire_owned().await.expect("The worker pool is never closed");
+		let handle = tokio::spawn({
+			let registry = Arc::clone(&registry);
+			let database = database.clone();
+			let timeouts = Arc::clone(&timeouts);
+			let fatal_sender = fatal_sender.clone();
+			async move {
+				let mut source = source;
+				if let Some(e) = fetch_source(&mut source, &registry, &database, &timeouts, hooks).await {
+					drop(fatal_sender.send(e));
+				}
+				drop(permit);
+			}
+		});
 
-		//Aborts program after running without hooks
+		//Aborts program after running without hooks, once every source was fetched
 		if !hooks {
-			exit(0);
+			running.push(handle);
+			fetched_once.insert(id);
+			if fetched_once.len() == enabled_count {
+				for handle in running.drain(..) {
+					drop(handle.await);
+				}
+				if let Ok(e) = fatal_receiver.try_recv() {
+					panic!("{:?}", e);
+				}
+				exit(0);
+			}
+		}
+	}
+
+	// Sources only leave the scheduler once they were disabled
+	let e = NewsError::BadSourceConfig("every source was disabled, as their robots.txt refuses them".to_owned());
+	if hooks {
+		error_webhook(&e, "The bot is now offline and needs investigation", false).await;
+	}
+	error!("{e}");
+	panic!("{:?}", e);
+}
+
+/// Fetches a single source, delivers everything new and handles its errors, returns those which should stop the bot
+async fn fetch_source(source: &mut Source, registry: &SourceRegistry, database: &Database, timeouts: &Mutex<Timeout>, hooks: bool) -> Option<NewsError> {
+	if timeouts.lock().await.is_timed_out(&source.name) {
+		return None;
+	}
+
+	increment(Incr::FetchCounter).await;
+	// Without any known URL everything listed would be new, which only happens when the source was unreachable so far
+	let result = if source.tracked_urls.is_empty() {
+		source.seed(database, registry).await.map(|()| vec![])
+	} else {
+		// The robots.txt is cached, so this only fetches it again once it expired
+		match source.apply_robots().await {
+			Ok(()) => match scrape_links(source, registry).await {
+				Ok(listed) => {
+					check_unlisted(source, registry, &listed, database, hooks).await;
+					html_processor(source, registry, listed).await
+				}
+				Err(e) => Err(e),
+			},
+			Err(e) => Err(e),
+		}
+	};
+	match result {
+		Ok(news) => {
+			let cutoff = source.max_age_cutoff(chrono::Utc::now().timestamp());
+			for news_embed in &news {
+				// Articles caught up on after a long downtime are still tracked and archived, but no longer news
+				if news_embed.published.map_or(false, |published| published < cutoff) {
+					warn!("{} is older than {} hours and is not delivered", news_embed.url, source.max_age);
+					continue;
+				}
+				if hooks {
+					// Every listed article was collected by this kind, so it resolves
+					if let Ok(news_source) = registry.resolve(source) {
+						news_embed.handle_webhooks(true, news_source, source).await;
+					}
+				}
+				increment(Incr::NewNews).await;
+			}
+
+			let urls: Vec<&str> = news.iter().map(|new| new.url.as_str()).collect();
+			source.store_recent(&urls);
+			let _db_insert_result = database.store_recent(&urls, source.id).await;
+			for news_embed in &news {
+				remember(database, source.id, news_embed).await;
+				record_version(news_embed, database, hooks).await;
+			}
+
+			recheck_articles(source, registry, database, hooks).await;
+			None
+		}
+		Err(e) => {
+			increment(Incr::Errors).await;
+			// Only the refused source stops, the scheduler drops it on its next turn
+			if let NewsError::RobotsDisallowed(_) = e {
+				error!("{} is disabled: {e}", source.name);
+				source.enabled = false;
+			}
+			handle_err(e, source.scrape_type, source.name.clone(), timeouts, hooks).await
 		}
 	}
 }
 
-/// Throws error as webhook, times out pages accordingly and terminates program if unrecoverable
-async fn handle_err(e: NewsError, scrape_type: ScrapeType, source: String, timeouts: &mut Timeout, hooks: bool) {
+/// Throws error as webhook, times out pages accordingly and returns the error if it is unrecoverable
+async fn handle_err(e: NewsError, scrape_type: ScrapeType, source: String, timeouts: &Mutex<Timeout>, hooks: bool) -> Option<NewsError> {
 	error!("{e}");
 	let crash_and_burn = |e: NewsError| async move {
 		if hooks {
 			error_webhook(&e, "The bot is now offline and needs investigation", false).await;
 		}
-		panic!("{:?}", e);
+		Some(e)
 	};
 
 	let time_out = |send_webhook_error_message, msg: String| async move {
@@ -141,7 +278,8 @@ async fn handle_err(e: NewsError, scrape_type: ScrapeType, source: String, timeo
 		if send_webhook_error_message {
 			error_webhook(&NewsError::SourceTimeout(scrape_type, msg, then), "", true).await;
 		}
-		let _ = &timeouts.time_out(source, then);
+		// Only locked for the insert, other workers check their timeouts meanwhile
+		timeouts.lock().await.time_out(source, then).await;
 	};
 
 	#[allow(clippy::match_wildcard_for_single_variants)]
@@ -149,7 +287,7 @@ async fn handle_err(e: NewsError, scrape_type: ScrapeType, source: String, timeo
 		NewsError::NoUrlOnPost(name, html) => {
 			let now = chrono::Local::now().timestamp();
 			let sanitized_url = name.replace(['/', ':'], "_");
-			drop(fs::write(&format!("/log/err_html/{sanitized_url}_{now}.html"), html));
+			drop(fs::write(format!("/log/err_html/{sanitized_url}_{now}.html"), html));
 			time_out(true, "no_url_on_post".to_owned()).await;
 		}
 		NewsError::MetaCannotBeScraped(_, ref url) => {
@@ -167,6 +305,16 @@ async fn handle_err(e: NewsError, scrape_type: ScrapeType, source: String, timeo
 		NewsError::SelectedNothing(source, _) => {
 			time_out(true, source).await;
 		}
+		NewsError::RobotsDisallowed(_) => {
+			error_webhook(&e, "The source is disabled until the bot restarts", true).await;
+		}
+		NewsError::FeedParse(_) => {
+			time_out(true, e.to_string()).await;
+		}
+		NewsError::Gone(_) => {
+			// Articles taken down are handled where they are checked, only listings which went away end up here
+			time_out(true, e.to_string()).await;
+		}
 		NewsError::SerenityError(_) => {
 			error_webhook(&e, "", true).await;
 		}
@@ -209,10 +357,12 @@ async fn handle_err(e: NewsError, scrape_type: ScrapeType, source: String, timeo
 			}
 		}
 		NewsError::SerdeJson(_) => {
-			todo!("Impelent this!");
+			// Only JSON sources can return malformed documents at runtime
+			time_out(true, e.to_string()).await;
 		}
 		_ => {
-			crash_and_burn(e).await;
+			return crash_and_burn(e).await;
 		}
 	}
+	None
 }
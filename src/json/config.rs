@@ -0,0 +1,153 @@
+use std::fs;
+use std::io::ErrorKind;
+
+pub const CONFIG_PATH: &str = "assets/config.json";
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Default)]
+/// General settings of the handler, every value falls back to its default when omitted
+pub struct Config {
+	#[serde(default)]
+	pub http: HttpConfig,
+	#[serde(default)]
+	pub database: DatabaseConfig,
+	#[serde(default)]
+	pub updates: UpdatesConfig,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+/// Settings for noticing edits of already delivered articles
+pub struct UpdatesConfig {
+	/// Hours after first seeing an article during which it is checked for edits
+	#[serde(default = "window_default")]
+	pub window: i64,
+	/// Minutes between two checks of the same article
+	#[serde(default = "recheck_interval_default")]
+	pub interval: i64,
+	/// Articles checked at most per fetch of their source
+	#[serde(default = "per_fetch_default")]
+	pub per_fetch: u32,
+}
+
+impl Default for UpdatesConfig {
+	fn default() -> Self {
+		Self {
+			window: window_default(),
+			interval: recheck_interval_default(),
+			per_fetch: per_fetch_default(),
+		}
+	}
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+/// Settings of the SQLite database keeping the news history
+pub struct DatabaseConfig {
+	/// File holding the database, created with its parent directories when missing
+	#[serde(default = "database_path_default")]
+	pub path: String,
+}
+
+impl Default for DatabaseConfig {
+	fn default() -> Self {
+		Self {
+			path: database_path_default(),
+		}
+	}
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+/// Settings of the HTTP client shared by all sources
+pub struct HttpConfig {
+	/// Sent with every request, its product token is also matched against robots.txt
+	#[serde(default = "user_agent_default")]
+	pub user_agent: String,
+	/// Seconds a request may take in total
+	#[serde(default = "timeout_default")]
+	pub timeout: u64,
+	/// Seconds establishing a connection may take
+	#[serde(default = "connect_timeout_default")]
+	pub connect_timeout: u64,
+	/// Routes all requests through this proxy, such as "http://localhost:3128"
+	#[serde(default)]
+	pub proxy: Option<String>,
+	/// Attempts made after a transient failure, such as a timeout or 503
+	#[serde(default = "max_retries_default")]
+	pub max_retries: u32,
+	/// Milliseconds waited before the first retry, doubled for every further retry
+	#[serde(default = "backoff_default")]
+	pub backoff: u64,
+	/// Seconds waited at most between two attempts, including Retry-After
+	#[serde(default = "max_backoff_default")]
+	pub max_backoff: u64,
+}
+
+impl Default for HttpConfig {
+	fn default() -> Self {
+		Self {
+			user_agent: user_agent_default(),
+			timeout: timeout_default(),
+			connect_timeout: connect_timeout_default(),
+			proxy: None,
+			max_retries: max_retries_default(),
+			backoff: backoff_default(),
+			max_backoff: max_backoff_default(),
+		}
+	}
+}
+
+fn user_agent_default() -> String {
+	format!("wt_event_handler/{} (+https://github.com/Warthunder-Open-Source-Foundation/wt_event_handler)", env!("CARGO_PKG_VERSION"))
+}
+
+fn database_path_default() -> String {
+	"assets/news.sqlite".to_owned()
+}
+
+const fn window_default() -> i64 {
+	48
+}
+
+const fn recheck_interval_default() -> i64 {
+	30
+}
+
+const fn per_fetch_default() -> u32 {
+	2
+}
+
+const fn timeout_default() -> u64 {
+	10
+}
+
+const fn connect_timeout_default() -> u64 {
+	5
+}
+
+const fn max_retries_default() -> u32 {
+	3
+}
+
+const fn backoff_default() -> u64 {
+	500
+}
+
+const fn max_backoff_default() -> u64 {
+	60
+}
+
+impl Config {
+	/// Reads the config file, a missing file uses the defaults
+	pub fn load() -> Self {
+		match fs::read(CONFIG_PATH) {
+			Ok(raw) => serde_json::from_slice(&raw).expect("The config file is malformed"),
+			Err(e) if e.kind() == ErrorKind::NotFound => Self::default(),
+			Err(e) => panic!("Cannot read the config file: {e}"),
+		}
+	}
+}
+
+impl HttpConfig {
+	/// The product token of the user agent, such as "wt_event_handler"
+	pub fn product_token(&self) -> &str {
+		self.user_agent.split(['/', ' ']).next().unwrap_or_default()
+	}
+}
\ No newline at end of file
@@ -1,3 +1,5 @@
 pub mod sources;
 pub mod webhooks;
-pub mod sources_def;
\ No newline at end of file
+pub mod sources_def;
+pub mod selectors;
+pub mod config;
\ No newline at end of file
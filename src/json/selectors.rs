@@ -0,0 +1,180 @@
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Eq)]
+/// Describes where a source lists its articles and where their meta-data lives
+pub struct Selectors {
+	/// Matches every listed article on the sources page
+	pub listing: String,
+	/// Matches the element carrying the link, relative to a listed article
+	pub link: String,
+	#[serde(default = "link_attribute_default")]
+	pub link_attribute: String,
+	/// Prepended to links that are not absolute, such as "https://warthunder.com"
+	#[serde(default)]
+	pub base_url: Option<String>,
+	/// Matches the link to the next listing page, such as "link[rel=\"next\"]"
+	#[serde(default)]
+	pub next_page: Option<String>,
+	/// Open Graph, Twitter and finally the title element by default
+	#[serde(default = "title_default")]
+	pub title: MetaSelector,
+	/// Open Graph, Twitter and finally the description meta tag by default
+	#[serde(default = "preview_default")]
+	pub preview: MetaSelector,
+	/// Open Graph and Twitter meta tags by default
+	#[serde(default = "image_default")]
+	pub image: MetaSelector,
+	/// Matches the content of an article, whose text is archived and compared to notice edits
+	#[serde(default)]
+	pub body: Option<String>,
+	/// Where the publish date of an article is stated, such as a time element
+	#[serde(default)]
+	pub published: Option<MetaSelector>,
+	/// Where the author of an article is shown, such as the author pane of the first forum post
+	#[serde(default)]
+	pub author: Option<AuthorSelectors>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Eq)]
+/// Describes the author of an article, selectors below `post` are relative to its first match
+pub struct AuthorSelectors {
+	/// Matches the post the author is taken from, such as "article"
+	pub post: String,
+	/// Matches the element whose text is the name of the author
+	pub name: String,
+	/// Matches the avatar image of the author
+	#[serde(default)]
+	pub avatar: Option<String>,
+	/// Matches the staff badge or rank of the author, such as "Developer"
+	#[serde(default)]
+	pub badge: Option<String>,
+}
+
+impl AuthorSelectors {
+	pub fn css_selectors(&self) -> Vec<&str> {
+		[&self.post, &self.name].into_iter().chain(&self.avatar).chain(&self.badge).map(String::as_str).collect()
+	}
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "from", rename_all = "snake_case")]
+/// Defines how a single embed field is collected from an article
+pub enum MetaSelector {
+	/// The first listed attribute present on any element matching the selector
+	Attribute {
+		selector: String,
+		attributes: Vec<String>,
+	},
+	/// The inner HTML of the first matching element with at least `min_length` bytes, anchors are turned into markdown
+	Text {
+		selector: String,
+		#[serde(default)]
+		min_length: usize,
+	},
+	/// The beginning of the text of the first matching element, one line per paragraph, such as the first post of a forum topic
+	ArticleText {
+		selector: String,
+	},
+	/// Constant text which is used for every article
+	Fixed {
+		text: String,
+	},
+	/// Content of the meta tag with the first listed name or property present, such as "og:title" before "twitter:title"
+	Meta {
+		names: Vec<String>,
+	},
+	/// The first selector in the chain which finds anything, such as meta tags falling back to an element
+	Chain {
+		chain: Vec<MetaSelector>,
+	},
+}
+
+impl MetaSelector {
+	/// Every CSS selector used, including those within a chain
+	pub fn css_selectors(&self) -> Vec<&str> {
+		match self {
+			Self::Attribute { selector, .. } | Self::Text { selector, .. } | Self::ArticleText { selector } => vec![selector],
+			Self::Fixed { .. } | Self::Meta { .. } => vec![],
+			Self::Chain { chain } => chain.iter().flat_map(Self::css_selectors).collect(),
+		}
+	}
+}
+
+fn meta(names: &[&str]) -> MetaSelector {
+	MetaSelector::Meta {
+		names: names.iter().map(|name| (*name).to_owned()).collect(),
+	}
+}
+
+fn title_default() -> MetaSelector {
+	MetaSelector::Chain {
+		chain: vec![
+			meta(&["og:title", "twitter:title"]),
+			MetaSelector::Text {
+				selector: "head > title".to_owned(),
+				min_length: 1,
+			},
+		],
+	}
+}
+
+fn preview_default() -> MetaSelector {
+	meta(&["og:description", "twitter:description", "description"])
+}
+
+fn image_default() -> MetaSelector {
+	meta(&["og:image", "og:image:secure_url", "twitter:image", "twitter:image:src"])
+}
+
+fn link_attribute_default() -> String {
+	"href".to_owned()
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Eq)]
+/// Describes where a JSON endpoint keeps its articles, paths are dot-separated keys or array indices such as "data.items"
+pub struct JsonFields {
+	/// Path to the list of articles, empty when the document itself is the list
+	#[serde(default)]
+	pub items: String,
+	/// Paths below are relative to a single article
+	pub url: String,
+	pub title: String,
+	#[serde(default)]
+	pub preview: Option<String>,
+	#[serde(default)]
+	pub image: Option<String>,
+	/// Path to the entire content of an article
+	#[serde(default)]
+	pub body: Option<String>,
+	/// Path to the publish date of an article
+	#[serde(default)]
+	pub published: Option<String>,
+	/// Prepended to article URLs that are not absolute
+	#[serde(default)]
+	pub base_url: Option<String>,
+	/// Path to the URL of the next listing page, relative to the document
+	#[serde(default)]
+	pub next: Option<String>,
+}
+
+impl Selectors {
+	/// Turns a listed link into an absolute URL
+	pub fn format_into_final_url(&self, top_url: &str) -> String {
+		format_into_final_url(self.base_url.as_deref(), top_url)
+	}
+}
+
+impl JsonFields {
+	pub fn format_into_final_url(&self, top_url: &str) -> String {
+		format_into_final_url(self.base_url.as_deref(), top_url)
+	}
+}
+
+fn format_into_final_url(base_url: Option<&str>, top_url: &str) -> String {
+	match base_url {
+		Some(base_url) if !top_url.starts_with("http") => {
+			format!("{base_url}{top_url}")
+		}
+		_ => {
+			top_url.to_owned()
+		}
+	}
+}
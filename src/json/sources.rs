@@ -1,32 +1,149 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use tracing::{error, warn};
 
 use crate::api::database::Database;
+use crate::embed::DEFAULT_LANGUAGE;
 use crate::error::NewsError;
-use crate::scrapers::html_processing::scrape_links;
+use crate::json::selectors::{JsonFields, Selectors};
+use crate::scrapers::html_processing::{get_embed_data, scrape_links};
+use crate::scrapers::news_source::SourceRegistry;
+use crate::scrapers::scraper_resources::robots;
 use crate::scrapers::scraper_resources::resources::ScrapeType;
+use crate::scrapers::versions::record_version;
+use crate::timeout::Timeout;
 
-#[derive(Default, serde::Serialize, serde::Deserialize, Debug)]
+// Seconds a source which is unreachable on startup is left alone
+const UNREACHABLE_TIMEOUT: i64 = 60 * 5;
+
+#[derive(Default, serde::Serialize, serde::Deserialize, Debug, Clone)]
 pub struct Sources {
 	pub sources: Vec<Source>,
 }
 
 pub type NewsArticle = HashMap<String, i64>;
 
-#[derive(serde::Serialize, serde::Deserialize, Debug)]
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
 pub struct Source {
 	pub name: String,
 	pub domain: String,
 	pub id: u8,
+	/// Category of the source, used for filters and display
 	pub scrape_type: ScrapeType,
+	/// Implementation driving this source, as registered in the SourceRegistry
+	#[serde(default = "kind_default")]
+	pub kind: String,
+	/// Language the source publishes in, such as "en" or "de"
+	#[serde(default = "language_default")]
+	pub language: String,
+	/// Amount of listing pages read at most when catching up on missed articles
+	#[serde(default = "max_pages_default")]
+	pub max_pages: usize,
+	/// Hours after which caught up articles are too old to be delivered, and further listing pages are not read
+	#[serde(default = "max_age_default")]
+	pub max_age: u64,
+	/// Seconds between two fetches of this source
+	#[serde(default = "interval_default")]
+	pub interval: u64,
+	/// Up to this many seconds are randomly added to each interval
+	#[serde(default = "jitter_default")]
+	pub jitter: u64,
+	/// Disabled sources are kept for API lookups, but never fetched
+	#[serde(default = "enabled_default")]
+	pub enabled: bool,
+	#[serde(default)]
+	pub selectors: Option<Selectors>,
+	/// Field paths for sources of the json kind
+	#[serde(default)]
+	pub json: Option<JsonFields>,
 	#[serde(skip_serializing, skip_deserializing)]
 	pub(crate) tracked_urls: NewsArticle,
+	/// Recent URLs which disappeared from the listing and were withdrawn already
+	#[serde(skip_serializing, skip_deserializing)]
+	pub(crate) unlisted: HashSet<String>,
+}
+
+const fn enabled_default() -> bool {
+	true
+}
+
+fn kind_default() -> String {
+	"html".to_owned()
+}
+
+const fn interval_default() -> u64 {
+	200
+}
+
+const fn jitter_default() -> u64 {
+	10
+}
+
+const fn max_pages_default() -> usize {
+	5
+}
+
+const fn max_age_default() -> u64 {
+	72
+}
+
+fn language_default() -> String {
+	DEFAULT_LANGUAGE.to_owned()
 }
 
 impl Source {
+	/// Applies the robots.txt of the source and seeds it when no URL of it is known yet
+	async fn prepare(&mut self, db: &Database, registry: &SourceRegistry) -> Result<(), NewsError> {
+		if self.tracked_urls.is_empty() {
+			self.seed(db, registry).await
+		} else {
+			self.apply_robots().await
+		}
+	}
+
+	/// Refuses the source if its robots.txt does not permit it, and clamps the interval to its Crawl-delay
+	pub async fn apply_robots(&mut self) -> Result<(), NewsError> {
+		robots::check_allowed(&self.domain).await?;
+
+		if let Some(crawl_delay) = robots::robots_for(&self.domain).await?.crawl_delay {
+			let min_interval = crawl_delay.as_secs_f64().ceil() as u64;
+			if self.interval < min_interval {
+				warn!("Clamping the interval of {} to the Crawl-delay of {min_interval} seconds", self.name);
+				self.interval = min_interval;
+			}
+		}
+		Ok(())
+	}
+
+	/// Tracks everything currently listed without delivering it, so only articles published afterwards are new.
+	/// Changelog sources also record the version of their newest changelog
+	pub async fn seed(&mut self, db: &Database, registry: &SourceRegistry) -> Result<(), NewsError> {
+		warn!("Seeding URLs of {}", self.name);
+		self.apply_robots().await?;
+		let urls = scrape_links(self, registry).await?;
+		self.store_recent(&urls);
+		let _db_insert_result = db.store_recent(&urls, self.id).await;
+
+		// The current version is known right away, instead of only once the next changelog is published
+		if let (ScrapeType::Changelog, Some(newest)) = (self.scrape_type, urls.first()) {
+			match get_embed_data(newest, self, registry).await {
+				// Fail-over embeds and changelogs without a recognisable version carry none, which would otherwise go unnoticed
+				Ok(embed) if embed.version.is_none() => warn!("Cannot read the version of {newest}: it names none, or the changelog could not be read"),
+				Ok(embed) => record_version(&embed, db, false).await,
+				Err(e) => warn!("Cannot read the version of {newest}: {e}"),
+			}
+		}
+		Ok(())
+	}
+
+	/// UTC timestamp before which articles are too old to catch up on
+	pub fn max_age_cutoff(&self, now: i64) -> i64 {
+		let max_age = i64::try_from(self.max_age.saturating_mul(60 * 60)).unwrap_or(i64::MAX);
+		now.saturating_sub(max_age)
+	}
+
 	pub fn is_new(&self, value: &str) -> bool {
-		self.tracked_urls.get(value).is_none()
+		!self.tracked_urls.contains_key(value)
 	}
 
 	pub fn store_recent<I>(&mut self, value: I)
@@ -41,33 +158,44 @@ impl Source {
 }
 
 impl Sources {
-	pub async fn build(db: &Database) -> Result<Self, NewsError> {
-		let recent = Self::new()
-			.pre_populate_urls(db.clone())
-			.await?;
-
-		Ok(recent)
+	/// Loads the sources and their known URLs, sources which cannot be reached yet start timed out
+	/// and those refused by their robots.txt are disabled, which fails when none is left
+	pub async fn build(db: &Database, registry: &SourceRegistry, timeouts: &mut Timeout) -> Result<Self, NewsError> {
+		Self::new(registry)?.prepare_all(db, registry, timeouts).await
 	}
 
-	async fn pre_populate_urls(self, db: Database) -> Result<Self, NewsError> {
-		warn!("Pre-fetching URLs");
-		let mut new = self;
-		for source in &mut new.sources {
-			match scrape_links(source).await {
-				Ok(news_urls) => {
-					for news_url in &news_urls {
-						source.store_recent([&news_url]);
-						db.store_recent([&news_url], source.id).await.unwrap();
-					}
-				}
+	async fn prepare_all(mut self, db: &Database, registry: &SourceRegistry, timeouts: &mut Timeout) -> Result<Self, NewsError> {
+		warn!("Loading known URLs");
+		for source in self.sources.iter_mut().filter(|source| source.enabled) {
+			// A broken database should not stop the bot either, the source is seeded once its timeout ended
+			let known = match db.get_known_urls(source.id).await {
+				Ok(known) => known,
 				Err(e) => {
-					error!("Failed to prefetch source-data: {}", e);
-					panic!("Failed to prefetch source-data: {}", e);
+					let until = chrono::Utc::now().timestamp() + UNREACHABLE_TIMEOUT;
+					error!("Cannot read the known URLs of {}, it starts timed out for {UNREACHABLE_TIMEOUT} seconds: {e}", source.name);
+					timeouts.time_out(source.name.clone(), until).await;
+					continue;
 				}
+			};
+			source.tracked_urls.extend(known);
+
+			if let Err(e) = source.prepare(db, registry).await {
+				// Refusals by robots.txt are deliberate, unlike a domain being down
+				if let NewsError::RobotsDisallowed(_) = e {
+					error!("{} is disabled: {e}", source.name);
+					source.enabled = false;
+					continue;
+				}
+				let until = chrono::Utc::now().timestamp() + UNREACHABLE_TIMEOUT;
+				error!("{} is unreachable and starts timed out for {UNREACHABLE_TIMEOUT} seconds: {e}", source.name);
+				timeouts.time_out(source.name.clone(), until).await;
 			}
 		}
 
-		Ok(new)
+		if !self.sources.iter().any(|source| source.enabled) {
+			return Err(NewsError::BadSourceConfig("every enabled source is refused by its robots.txt".to_owned()));
+		}
+		Ok(self)
 	}
 
 	/// Removes any URL from all tracked URLs
@@ -89,3 +217,81 @@ impl Sources {
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use async_trait::async_trait;
+
+	use crate::api::database::Database;
+	use crate::embed::EmbedData;
+	use crate::error::NewsError;
+	use crate::json::sources::{Source, Sources};
+	use crate::scrapers::news_source::{ListedPage, NewsSource, SourceRegistry};
+	use crate::scrapers::scraper_resources::robots::cache_robots;
+	use crate::timeout::Timeout;
+
+	#[derive(Debug)]
+	/// Behaves like a source whose domain is down
+	struct Unreachable;
+
+	#[async_trait]
+	impl NewsSource for Unreachable {
+		async fn listed_page(&self, _source: &Source, page_url: &str) -> Result<Option<ListedPage>, NewsError> {
+			Err(NewsError::SelectedNothing("listing".to_owned(), page_url.to_owned()))
+		}
+
+		fn normalise_url(&self, _source: &Source, url: &str) -> String {
+			url.to_owned()
+		}
+
+		async fn embed_data(&self, source: &Source, url: &str) -> Result<EmbedData, NewsError> {
+			Ok(EmbedData::fail_over(url, source.scrape_type))
+		}
+
+		fn validate(&self, _source: &Source) -> Result<(), String> {
+			Ok(())
+		}
+	}
+
+	async fn prepare(raw: &[u8], name: &str) -> (Result<Sources, NewsError>, Timeout) {
+		cache_robots("https://refused.example", "User-agent: *\nDisallow: /").await;
+		cache_robots("https://down.example", "").await;
+
+		let mut registry = SourceRegistry::empty();
+		registry.register("unreachable", Unreachable);
+		let path = std::env::temp_dir().join(format!("wt_event_handler_{name}_{}.sqlite", std::process::id()));
+		let db = Database::new(path.to_str().unwrap()).await.unwrap();
+		let mut timeouts = Timeout::new();
+
+		let sources = Sources::from_slice(raw, &registry).unwrap().prepare_all(&db, &registry, &mut timeouts).await;
+		drop(db);
+		drop(std::fs::remove_file(path));
+		(sources, timeouts)
+	}
+
+	#[tokio::test]
+	async fn test_unreachable_sources_start_timed_out() {
+		let raw = br#"{"sources": [
+			{"name": "refused", "domain": "https://refused.example/news", "id": 0, "scrape_type": "Main", "kind": "unreachable"},
+			{"name": "down", "domain": "https://down.example/news", "id": 1, "scrape_type": "Main", "kind": "unreachable"}
+		]}"#;
+		let (sources, timeouts) = prepare(raw, "unreachable").await;
+		let sources = sources.unwrap();
+
+		// Refusals by robots.txt disable the source, a domain being down only times it out
+		assert!(!sources.sources[0].enabled);
+		assert!(!timeouts.is_timed_out("refused"));
+		assert!(sources.sources[1].enabled);
+		assert!(timeouts.is_timed_out("down"));
+	}
+
+	#[tokio::test]
+	async fn test_build_fails_without_enabled_sources() {
+		let raw = br#"{"sources": [
+			{"name": "refused", "domain": "https://refused.example/news", "id": 0, "scrape_type": "Main", "kind": "unreachable"},
+			{"name": "disabled", "domain": "https://down.example/news", "id": 1, "scrape_type": "Main", "kind": "unreachable", "enabled": false}
+		]}"#;
+		let (sources, _) = prepare(raw, "refused").await;
+		assert!(matches!(sources, Err(NewsError::BadSourceConfig(_))));
+	}
+}
@@ -1,59 +1,122 @@
-use std::collections::HashMap;
+use std::cmp::Reverse;
+use std::collections::HashSet;
+use std::fs;
 
+use reqwest::Url;
+
+use crate::error::NewsError;
 use crate::json::sources::{Source, Sources};
-use crate::scrapers::scraper_resources::resources::ScrapeType;
+use crate::scrapers::news_source::SourceRegistry;
+
+pub const SOURCES_PATH: &str = "assets/sources.json";
 
 impl Sources {
-	pub(crate) fn new() -> Self {
-		Self {
-			sources: vec![
-				Source {
-					name: "warthunder_news".to_owned(),
-					domain: "https://warthunder.com/en/news".to_owned(),
-					id: 0,
-					scrape_type: ScrapeType::Main,
-					tracked_urls: HashMap::new(),
-				},
-				Source {
-					name: "warthunder_changelog".to_owned(),
-					domain: "https://warthunder.com/en/game/changelog/".to_owned(),
-					id: 1,
-					scrape_type: ScrapeType::Changelog,
-					tracked_urls: HashMap::new(),
-				},
-				Source {
-					name: "forums_updates_information".to_owned(),
-					domain: "https://forum.warthunder.com/index.php?/forum/126-updates-information-read-only/&ct=1626882238".to_owned(),
-					id: 2,
-					scrape_type: ScrapeType::Forum,
-					tracked_urls: HashMap::new(),
-				},
-				Source {
-					name: "forums_project_news".to_owned(),
-					domain: "https://forum.warthunder.com/index.php?/forum/26-project-news-read-only/&ct=1630343851".to_owned(),
-					id: 3,
-					scrape_type: ScrapeType::Forum,
-					tracked_urls: HashMap::new(),
-				},
-				Source {
-					name: "forums_notice_board".to_owned(),
-					domain: "https://forum.warthunder.com/index.php?/forum/1500-notice-board-announcements-information/".to_owned(),
-					id: 4,
-					scrape_type: ScrapeType::Forum,
-					tracked_urls: HashMap::new(),
-				},
-			]
-		}
+	/// Loads all source definitions from the config file and validates them
+	pub(crate) fn new(registry: &SourceRegistry) -> Result<Self, NewsError> {
+		let raw = fs::read(SOURCES_PATH)?;
+		Self::from_slice(&raw, registry)
 	}
-	pub fn id_from_name(name: &str) -> u8 {
-		#[allow(clippy::match_same_arms)]
-		match name {
-			"warthunder_news" => 0,
-			"warthunder_changelog" => 1,
-			"forums_updates_information" => 2,
-			"forums_project_news" => 3,
-			"forums_notice_board" => 4,
-			_ => 0,
+
+	pub(crate) fn from_slice(raw: &[u8], registry: &SourceRegistry) -> Result<Self, NewsError> {
+		let sources: Self = serde_json::from_slice(raw)?;
+		sources.validate(registry)?;
+		Ok(sources)
+	}
+
+	/// Rejects definitions which would collide in the database or cannot be fetched
+	fn validate(&self, registry: &SourceRegistry) -> Result<(), NewsError> {
+		let mut ids = HashSet::new();
+		let mut names = HashSet::new();
+
+		for source in &self.sources {
+			if source.name.trim().is_empty() {
+				return Err(NewsError::BadSourceConfig(format!("source with id {} has no name", source.id)));
+			}
+			if !(source.domain.starts_with("https://") || source.domain.starts_with("http://")) {
+				return Err(NewsError::BadSourceConfig(format!("source {} has no valid domain: \'{}\'", source.name, source.domain)));
+			}
+			if source.language.is_empty() || source.language.chars().any(|c| !c.is_ascii_lowercase()) {
+				return Err(NewsError::BadSourceConfig(format!("source {} has an invalid language: \'{}\'", source.name, source.language)));
+			}
+			if source.interval == 0 {
+				return Err(NewsError::BadSourceConfig(format!("source {} needs an interval of at least one second", source.name)));
+			}
+			if !ids.insert(source.id) {
+				return Err(NewsError::BadSourceConfig(format!("id {} is used more than once", source.id)));
+			}
+			if !names.insert(source.name.as_str()) {
+				return Err(NewsError::BadSourceConfig(format!("name {} is used more than once", source.name)));
+			}
+
+			registry.resolve(source)?
+				.validate(source)
+				.map_err(|reason| NewsError::BadSourceConfig(format!("source {}: {reason}", source.name)))?;
 		}
+
+		if !self.sources.iter().any(|source| source.enabled) {
+			return Err(NewsError::BadSourceConfig("no source is enabled".to_owned()));
+		}
+		Ok(())
+	}
+
+	pub fn id_from_name(&self, name: &str) -> Option<u8> {
+		self.sources.iter()
+			.find(|source| source.name == name)
+			.map(|source| source.id)
+	}
+
+	/// Source an article was most likely listed on, the one on the same host whose domain shares the longest beginning with the URL
+	pub fn source_for_url(&self, url: &str) -> Option<&Source> {
+		let host = |url: &str| Url::parse(url).ok().and_then(|url| url.host_str().map(ToOwned::to_owned));
+		let url_host = host(url)?;
+		self.sources.iter()
+			.filter(|source| host(&source.domain).as_ref() == Some(&url_host))
+			.max_by_key(|source| {
+				let shared = source.domain.bytes().zip(url.bytes()).take_while(|(a, b)| a == b).count();
+				(shared, Reverse(source.id))
+			})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::json::sources::Sources;
+	use crate::json::sources_def::SOURCES_PATH;
+	use crate::scrapers::news_source::SourceRegistry;
+
+	#[test]
+	fn shipped_config_is_valid() {
+		let raw = std::fs::read(SOURCES_PATH).unwrap();
+		let sources = Sources::from_slice(&raw, &SourceRegistry::default()).unwrap();
+		assert_eq!(sources.id_from_name("warthunder_changelog"), Some(1));
+		assert_eq!(sources.id_from_name("does_not_exist"), None);
+	}
+
+	#[test]
+	fn source_is_found_by_url() {
+		let raw = br#"{"sources": [
+			{"name": "news", "domain": "https://example.com/en/news", "id": 0, "scrape_type": "Main", "kind": "feed"},
+			{"name": "changelog", "domain": "https://example.com/en/game/changelog/", "id": 1, "scrape_type": "Changelog", "kind": "feed"},
+			{"name": "forum_a", "domain": "https://forum.example.com/index.php?/forum/1-a/", "id": 2, "scrape_type": "Forum", "kind": "feed"},
+			{"name": "forum_b", "domain": "https://forum.example.com/index.php?/forum/2-b/", "id": 3, "scrape_type": "Forum", "kind": "feed"}
+		]}"#;
+		let sources = Sources::from_slice(raw, &SourceRegistry::default()).unwrap();
+		let id = |url| sources.source_for_url(url).map(|source| source.id);
+
+		assert_eq!(id("https://example.com/en/news/8000-devblog"), Some(0));
+		assert_eq!(id("https://example.com/en/game/changelog/current/1500"), Some(1));
+		// Topics are not listed below their forum, the first forum of the host is taken
+		assert_eq!(id("https://forum.example.com/index.php?/topic/1-a-topic/"), Some(2));
+		assert_eq!(id("https://elsewhere.com/en/news/8000-devblog"), None);
+	}
+
+	#[test]
+	fn duplicate_id_is_rejected() {
+		let raw = br#"{"sources": [
+			{"name": "news", "domain": "https://example.com/news", "id": 0, "scrape_type": "Main", "selectors": {"listing": "li", "link": "a"}},
+			{"name": "forum", "domain": "https://forum.example.com", "id": 0, "scrape_type": "Forum", "selectors": {"listing": "li", "link": "a"}}
+		]}"#;
+		let error = Sources::from_slice(raw, &SourceRegistry::default()).unwrap_err();
+		assert!(error.to_string().contains("id 0 is used more than once"));
 	}
-}
\ No newline at end of file
+}
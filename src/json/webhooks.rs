@@ -1,9 +1,13 @@
 use std::io;
 use std::process::exit;
 
+use serde::{Deserialize, Deserializer};
+use serde::de::Error;
 use serenity::http::Http;
 use tracing::error;
 
+use crate::embed::DEFAULT_LANGUAGE;
+
 #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq, Eq, Clone)]
 /// Stores Discord tokens
 pub struct WebhookAuth {
@@ -31,6 +35,55 @@ pub struct Hooks {
 	pub forum_filter: FilterType,
 	pub main_keywords: Vec<String>,
 	pub forum_keywords: Vec<String>,
+	/// Only news from sources in these languages are delivered
+	#[serde(default = "languages_default")]
+	pub languages: Vec<String>,
+	/// Notices about already delivered articles this webhook wants, none by default
+	#[serde(default)]
+	pub notify: Notifications,
+	/// Reminders about events announced by the news, none by default
+	#[serde(default)]
+	pub reminders: Reminders,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq, Eq, Clone, Default)]
+/// Notices about already delivered articles a webhook opted into
+pub struct Notifications {
+	/// Articles being edited after they were delivered
+	#[serde(default)]
+	pub updates: bool,
+	/// Articles being taken down after they were delivered
+	#[serde(default)]
+	pub withdrawals: bool,
+	/// New client versions announced by changelogs, whether or not the changelog itself passes the filters
+	#[serde(default)]
+	pub versions: bool,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq, Eq, Clone, Default)]
+/// Minutes before events a webhook is reminded of them
+pub struct Reminders {
+	/// Such as 60 for "starts in 1 hour"
+	#[serde(default, deserialize_with = "leads")]
+	pub starts: Vec<i64>,
+	/// Such as 1440 for "ends in 24 hours"
+	#[serde(default, deserialize_with = "leads")]
+	pub ends: Vec<i64>,
+}
+
+/// Rejects leads below one minute, which would remind of an event once it already started or ended
+fn leads<'de, D>(deserializer: D) -> Result<Vec<i64>, D::Error>
+	where D: Deserializer<'de>
+{
+	let leads = Vec::<i64>::deserialize(deserializer)?;
+	match leads.iter().find(|lead| **lead < 1) {
+		Some(lead) => Err(D::Error::custom(format!("reminder lead of {lead} minutes, leads need to be at least 1"))),
+		None => Ok(leads),
+	}
+}
+
+fn languages_default() -> Vec<String> {
+	vec![DEFAULT_LANGUAGE.to_owned()]
 }
 
 #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq, Eq, Clone)]
@@ -43,8 +96,9 @@ pub struct StatisticsHook {
 	pub time_between_post: u64,
 }
 
-#[derive(serde::Serialize, serde::Deserialize, PartialEq, Eq, Debug, Copy, Clone)]
+#[derive(serde::Serialize, serde::Deserialize, PartialEq, Eq, Debug, Copy, Clone, Default)]
 pub enum FilterType {
+	#[default]
 	Default = 0,
 	Blacklist = 1,
 	Whitelist = 2,
@@ -70,13 +124,12 @@ impl FilterType {
 	}
 }
 
-impl Default for FilterType {
-	fn default() -> Self {
-		Self::Default
+impl Hooks {
+	/// Whether the webhook receives news of sources in this language
+	pub fn reads(&self, language: &str) -> bool {
+		self.languages.iter().any(|read| read == language)
 	}
-}
 
-impl Hooks {
 	pub async fn from_user() -> Self {
 		let mut val = Self {
 			name: String::new(),
@@ -86,6 +139,9 @@ impl Hooks {
 			forum_filter: FilterType::default(),
 			main_keywords: vec![],
 			forum_keywords: vec![],
+			languages: languages_default(),
+			notify: Notifications::default(),
+			reminders: Reminders::default(),
 		};
 		let mut line = String::new();
 
@@ -145,6 +201,38 @@ impl Hooks {
 			val.forum_keywords = line.split_whitespace().map(String::from).collect();
 		}
 
+		println!("Enter the languages to receive news in, seperated by spaces (such as \"en de\"), leave empty for english only");
+		line.clear();
+		io::stdin().read_line(&mut line).unwrap();
+		let languages: Vec<String> = line.split_whitespace().map(str::to_lowercase).collect();
+		if !languages.is_empty() {
+			val.languages = languages;
+		}
+
+		println!("Should the webhook be told when delivered articles are edited? y/n");
+		line.clear();
+		io::stdin().read_line(&mut line).unwrap();
+		val.notify.updates = line.trim() == "y";
+
+		println!("Should the webhook be told when delivered articles are taken down? y/n");
+		line.clear();
+		io::stdin().read_line(&mut line).unwrap();
+		val.notify.withdrawals = line.trim() == "y";
+
+		println!("Should the webhook be told about new game versions? y/n");
+		line.clear();
+		io::stdin().read_line(&mut line).unwrap();
+		val.notify.versions = line.trim() == "y";
+
+		println!("Enter the minutes before events start to remind of them, seperated by spaces (such as \"60 1440\"), leave empty for none");
+		line.clear();
+		io::stdin().read_line(&mut line).unwrap();
+		val.reminders.starts = line.split_whitespace().filter_map(|lead| lead.parse().ok()).filter(|lead| *lead >= 1).collect();
+
+		println!("Enter the minutes before events end to remind of them, leave empty for none");
+		line.clear();
+		io::stdin().read_line(&mut line).unwrap();
+		val.reminders.ends = line.split_whitespace().filter_map(|lead| lead.parse().ok()).filter(|lead| *lead >= 1).collect();
 
 		println!("Entry created successfully, do you want to send a test-message to test the hook? y/n \n");
 		line.clear();
@@ -180,7 +268,40 @@ async fn send_test_hook(hook: &Hooks) {
 
 
 	webhook.execute(my_http_client, false, |w| {
-		w.content(&format!("Webhook {} was successfully created", &hook.name));
+		w.content(format!("Webhook {} was successfully created", &hook.name));
 		w
 	}).await.unwrap();
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::json::webhooks::{Hooks, Reminders};
+
+	static HOOK: &str = r#"{"name": "hook", "token": "", "uid": 0, "main_filter": "Default", "forum_filter": "Default", "main_keywords": [], "forum_keywords": []}"#;
+
+	#[test]
+	fn hooks_read_english_by_default() {
+		let hook: Hooks = serde_json::from_str(HOOK).unwrap();
+		assert!(hook.reads("en"));
+		assert!(!hook.reads("de"));
+	}
+
+	#[test]
+	fn hooks_read_every_listed_language() {
+		let mut hook: Hooks = serde_json::from_str(HOOK).unwrap();
+		hook.languages = vec!["de".to_owned(), "pl".to_owned()];
+		assert!(hook.reads("de"));
+		assert!(hook.reads("pl"));
+		// Listing languages replaces the default
+		assert!(!hook.reads("en"));
+	}
+
+	#[test]
+	fn reminder_leads_below_one_minute_are_rejected() {
+		let reminders: Reminders = serde_json::from_str(r#"{"starts": [60, 1], "ends": [1440]}"#).unwrap();
+		assert_eq!(reminders.starts, vec![60, 1]);
+
+		assert!(serde_json::from_str::<Reminders>(r#"{"starts": [60, 0]}"#).is_err());
+		assert!(serde_json::from_str::<Reminders>(r#"{"ends": [-30]}"#).is_err());
+	}
+}
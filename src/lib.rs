@@ -0,0 +1,66 @@
+// Reason: Keeps type name collisions at bay
+#![allow(clippy::module_name_repetitions)]
+
+// Reason: Just makes unwrap_or calls much more verbose than they need to be
+#![allow(clippy::or_fun_call)]
+
+// Reason: NewsError wraps the errors of serenity and reqwest, boxing them would only clutter every ? on them
+#![allow(clippy::result_large_err)]
+
+//! Fetches War Thunder news and delivers them to Discord webhooks.
+//! Further kinds of sources implement [`NewsSource`] and are registered in the [`SourceRegistry`] passed to [`fetch_loop`].
+
+use std::fs;
+use std::time::Instant;
+
+use lazy_static::lazy_static;
+use rand::Rng;
+
+use crate::json::config::Config;
+use crate::json::webhooks::CrashHook;
+use crate::json::webhooks::WebhookAuth;
+
+pub use crate::embed::EmbedData;
+pub use crate::error::NewsError;
+pub use crate::fetch_loop::fetch_loop;
+pub use crate::json::sources::Source;
+pub use crate::scrapers::news_source::{ListedPage, NewsSource, SourceRegistry};
+pub use crate::scrapers::scraper_resources::resources::ScrapeType;
+
+mod webhook_handler;
+pub mod scrapers;
+pub mod json;
+pub mod menu_options;
+pub mod fetch_loop;
+pub mod embed;
+pub mod error;
+mod timeout;
+mod scheduler;
+mod reminders;
+mod statistics;
+mod api;
+
+const TOKEN_PATH: &str = "assets/discord_token.json";
+
+
+lazy_static! {
+	pub static ref WEBHOOK_AUTH: WebhookAuth = {
+		let raw = fs::read(TOKEN_PATH).unwrap();
+		let json: WebhookAuth = serde_json::from_slice(&raw).unwrap();
+		json
+	};
+	pub static ref CONFIG: Config = Config::load();
+	pub static ref PANIC_INFO: CrashHook = {
+		WEBHOOK_AUTH.crash_hook[0].clone()
+	};
+	pub static ref SHUTDOWN_KEY: String =  {
+		rand::thread_rng()
+        .sample_iter(&rand::distributions::Alphanumeric)
+        .take(20)
+        .map(char::from)
+        .collect()
+	};
+	pub static ref BOOT_TIME: Instant =  {
+		Instant::now()
+	};
+}
@@ -4,58 +4,22 @@
 // Reason: Just makes unwrap_or calls much more verbose than they need to be
 #![allow(clippy::or_fun_call)]
 
-use std::{env, fs, io};
+// Reason: NewsError wraps the errors of serenity and reqwest, boxing them would only clutter every ? on them
+#![allow(clippy::result_large_err)]
+
+use std::{env, io};
 use std::io::stdout;
 use std::process::exit;
-use std::time::Instant;
 
-use lazy_static::{initialize, lazy_static};
-use rand::Rng;
-use tracing::{info, Level, warn};
+use lazy_static::initialize;
+use tracing::{Level, warn};
 use tracing_appender::rolling;
 use tracing_subscriber::EnvFilter;
 use tracing_subscriber::fmt::writer::MakeWriterExt;
 
-use crate::error::NewsError;
-use crate::fetch_loop::fetch_loop;
-use crate::json::webhooks::CrashHook;
-use crate::json::webhooks::WebhookAuth;
-use crate::menu_options::{add_webhook, remove_webhook, test_hook};
-
-mod webhook_handler;
-mod scrapers;
-mod json;
-mod menu_options;
-mod fetch_loop;
-mod embed;
-mod error;
-mod timeout;
-mod statistics;
-mod api;
-
-const TOKEN_PATH: &str = "assets/discord_token.json";
-
-
-lazy_static! {
-	pub static ref WEBHOOK_AUTH: WebhookAuth = {
-		let raw = fs::read(TOKEN_PATH).unwrap();
-		let json: WebhookAuth = serde_json::from_slice(&raw).unwrap();
-		json
-	};
-	pub static ref PANIC_INFO: CrashHook = {
-		WEBHOOK_AUTH.crash_hook[0].clone()
-	};
-	pub static ref SHUTDOWN_KEY: String =  {
-		rand::thread_rng()
-        .sample_iter(&rand::distributions::Alphanumeric)
-        .take(20)
-        .map(char::from)
-        .collect()
-	};
-	pub static ref BOOT_TIME: Instant =  {
-		Instant::now()
-	};
-}
+use wt_event_handler::{CONFIG, fetch_loop, NewsError, PANIC_INFO, SHUTDOWN_KEY, SourceRegistry, WEBHOOK_AUTH};
+use wt_event_handler::menu_options::{add_webhook, remove_webhook, test_hook};
+use wt_event_handler::scrapers::scraper_resources::http::HTTP_CLIENT;
 
 #[tokio::main]
 async fn main() -> Result<(), NewsError> {
@@ -63,14 +27,15 @@ async fn main() -> Result<(), NewsError> {
 	initialize(&WEBHOOK_AUTH);
 	initialize(&PANIC_INFO);
 	initialize(&SHUTDOWN_KEY);
+	initialize(&CONFIG);
+	initialize(&HTTP_CLIENT);
 
 	println!("Emergency shutdown param: localhost:8082/settings/shutdown/{}", *SHUTDOWN_KEY);
 
 	let mut line = String::new();
 	let mut hooks = true;
 
-	let mut args = env::args();
-	if let Some(first) = args.skip(1).next() {
+	if let Some(first) = env::args().nth(1) {
 		line = first;
 		println!("Launching automatically with option {}", &line)
 	} else {
@@ -127,6 +92,6 @@ async fn main() -> Result<(), NewsError> {
 	};
 
 	warn!("Started core loop");
-	fetch_loop(hooks).await;
+	fetch_loop(hooks, SourceRegistry::default()).await;
 	Ok(())
 }
\ No newline at end of file
@@ -0,0 +1,143 @@
+use std::time::Duration;
+
+use tracing::{error, warn};
+
+use crate::api::database::Database;
+use crate::api::database_queries::Event;
+use crate::embed::{EMPTY_IMG, EmbedData};
+use crate::fetch_loop::STATS;
+use crate::json::sources::Source;
+use crate::json::webhooks::Reminders;
+use crate::scrapers::news_source::SourceRegistry;
+use crate::statistics::Incr;
+use crate::WEBHOOK_AUTH;
+use crate::webhook_handler::deliver_notice;
+
+// Seconds between two checks for due reminders
+const REMINDER_TICK: u64 = 60;
+
+// Seconds a reminder may be late, those missed for longer (such as during downtime) are dropped as they would be wrong
+const REMINDER_GRACE: i64 = 60 * 15;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// A reminder of an event which is due
+struct Reminder {
+	/// "start" or "end"
+	kind: &'static str,
+	/// Minutes before the start or end
+	lead: i64,
+	/// When the reminder is due as UTC timestamp
+	due: i64,
+}
+
+/// Delivers the reminders webhooks asked for, forever. Events are filtered like the articles of the source they were found on
+pub async fn remind(database: Database, sources: Vec<Source>, registry: SourceRegistry) {
+	warn!("Spawned reminder thread");
+	loop {
+		tokio::time::sleep(Duration::from_secs(REMINDER_TICK)).await;
+		let now = chrono::Utc::now().timestamp();
+		let events = match database.get_events(now).await {
+			Ok(events) => events,
+			Err(e) => {
+				error!("{e}");
+				continue;
+			}
+		};
+
+		for event in &events {
+			let Some(source) = sources.iter().find(|source| source.id == event.source) else {
+				continue;
+			};
+			let Ok(news_source) = registry.resolve(source) else {
+				continue;
+			};
+			for (i, hook) in WEBHOOK_AUTH.hooks.iter().enumerate() {
+				if !hook.reads(&source.language) || !news_source.matches_filter(source, &event.url, hook) {
+					continue;
+				}
+				for reminder in due_reminders(event, &hook.reminders, now) {
+					match database.claim_reminder(event.id, hook.uid, reminder.kind, reminder.lead, reminder.due).await {
+						Ok(true) => {}
+						Ok(false) => {
+							continue;
+						}
+						Err(e) => {
+							error!("{e}");
+							continue;
+						}
+					}
+					let verb = if reminder.kind == "start" { "starts" } else { "ends" };
+					let notice = format!("{} {verb} in {}", event.title, describe_lead(reminder.lead));
+					let embed = EmbedData::new(&event.title, &event.url, EMPTY_IMG, "", source.scrape_type);
+					deliver_notice(&embed, "Reminder", &notice, i).await;
+					STATS.lock().await.increment(Incr::PostCounter);
+				}
+			}
+		}
+	}
+}
+
+/// Reminders which are due now, but not for longer than the grace period and not after what they remind of
+fn due_reminders(event: &Event, reminders: &Reminders, now: i64) -> Vec<Reminder> {
+	let starts = reminders.starts.iter().map(|lead| ("start", *lead, Some(event.start_date)));
+	let ends = reminders.ends.iter().map(|lead| ("end", *lead, event.end_date));
+	starts.chain(ends)
+		.filter_map(|(kind, lead, date)| {
+			let date = date?;
+			let due = date - lead * 60;
+			(due <= now && now < date && now < due + REMINDER_GRACE).then_some(Reminder {
+				kind,
+				lead,
+				due,
+			})
+		})
+		.collect()
+}
+
+/// Such as "1 hour", "30 minutes" or "2 days"
+fn describe_lead(minutes: i64) -> String {
+	let (amount, unit) = match minutes {
+		_ if minutes % (60 * 24) == 0 && minutes >= 60 * 48 => (minutes / (60 * 24), "day"),
+		_ if minutes % 60 == 0 => (minutes / 60, "hour"),
+		_ => (minutes, "minute"),
+	};
+	if amount == 1 {
+		format!("{amount} {unit}")
+	} else {
+		format!("{amount} {unit}s")
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::api::database_queries::Event;
+	use crate::json::webhooks::Reminders;
+	use crate::reminders::{describe_lead, due_reminders, Reminder};
+
+	#[test]
+	fn test_due_reminders() {
+		let event = Event {
+			id: 1,
+			source: 0,
+			url: String::new(),
+			title: "The Battle for Arachis".to_owned(),
+			start_date: 100_000,
+			end_date: Some(400_000),
+			all_day: false,
+		};
+		let reminders = Reminders {
+			starts: vec![60],
+			ends: vec![60 * 24],
+		};
+		assert_eq!(due_reminders(&event, &reminders, 96_400), vec![Reminder { kind: "start", lead: 60, due: 96_400 }]);
+		assert!(due_reminders(&event, &reminders, 96_399).is_empty());
+		// Too late to be right anymore
+		assert!(due_reminders(&event, &reminders, 99_999).is_empty());
+		assert_eq!(due_reminders(&event, &reminders, 313_700), vec![Reminder { kind: "end", lead: 60 * 24, due: 313_600 }]);
+
+		assert_eq!(describe_lead(60), "1 hour");
+		assert_eq!(describe_lead(60 * 24), "24 hours");
+		assert_eq!(describe_lead(60 * 24 * 3), "3 days");
+		assert_eq!(describe_lead(90), "90 minutes");
+	}
+}
\ No newline at end of file
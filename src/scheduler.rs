@@ -0,0 +1,119 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use rand::Rng;
+use tokio::time::Instant;
+
+use crate::json::sources::Source;
+
+#[derive(Clone, Debug)]
+/// Keeps track of when each source is due to be fetched again
+pub struct Scheduler {
+	next_due: HashMap<u8, Instant>,
+	/// Interval and jitter per source, so that sources can be rescheduled while they are being fetched
+	timing: HashMap<u8, (u64, u64)>,
+}
+
+impl Scheduler {
+	/// Schedules every enabled source within its jitter, so that they do not all start at once
+	pub fn new(sources: &[Source]) -> Self {
+		let now = Instant::now();
+		let timing: HashMap<u8, (u64, u64)> = sources.iter()
+			.filter(|source| source.enabled)
+			.map(|source| (source.id, (source.interval, source.jitter)))
+			.collect();
+		Self {
+			next_due: timing.iter()
+				.map(|(id, (_, jitter))| (*id, now + random_jitter(*jitter)))
+				.collect(),
+			timing,
+		}
+	}
+
+	/// Returns the source that is due the soonest and when
+	pub fn next_due(&self) -> Option<(u8, Instant)> {
+		self.next_due.iter()
+			.min_by_key(|(_, due)| **due)
+			.map(|(id, due)| (*id, *due))
+	}
+
+	/// Takes over the current interval of a source, such as one clamped to a Crawl-delay since
+	pub fn update(&mut self, source: &Source) {
+		self.timing.insert(source.id, (source.interval, source.jitter));
+	}
+
+	/// Schedules the next fetch of a source one interval from now
+	pub fn reschedule(&mut self, id: u8) {
+		let (interval, jitter) = self.timing.get(&id).copied().unwrap_or_default();
+		let due = Instant::now() + Duration::from_secs(interval) + random_jitter(jitter);
+		self.next_due.insert(id, due);
+	}
+
+	/// Stops scheduling a source, such as one disabled while running
+	pub fn remove(&mut self, id: u8) {
+		self.next_due.remove(&id);
+		self.timing.remove(&id);
+	}
+}
+
+fn random_jitter(jitter: u64) -> Duration {
+	Duration::from_secs(rand::thread_rng().gen_range(0..=jitter))
+}
+#[cfg(test)]
+mod tests {
+	use std::time::Duration;
+
+	use tokio::time::Instant;
+
+	use crate::json::sources::{Source, Sources};
+	use crate::scheduler::Scheduler;
+	use crate::scrapers::news_source::SourceRegistry;
+
+	/// Sources 0 and 1 are fetched every 100 and 200 seconds with up to 10 seconds jitter, source 2 is disabled
+	fn sources() -> Vec<Source> {
+		let raw = br#"{"sources": [
+			{"name": "a", "domain": "https://example.com/a", "id": 0, "scrape_type": "Main", "kind": "feed", "interval": 100, "jitter": 10},
+			{"name": "b", "domain": "https://example.com/b", "id": 1, "scrape_type": "Main", "kind": "feed", "interval": 200, "jitter": 10},
+			{"name": "c", "domain": "https://example.com/c", "id": 2, "scrape_type": "Main", "kind": "feed", "enabled": false}
+		]}"#;
+		Sources::from_slice(raw, &SourceRegistry::default()).unwrap().sources
+	}
+
+	#[tokio::test(start_paused = true)]
+	async fn test_due_within_jitter() {
+		let start = Instant::now();
+		let mut scheduler = Scheduler::new(&sources());
+		for id in [0, 1] {
+			let due = scheduler.next_due.get(&id).copied().unwrap();
+			assert!(start <= due && due <= start + Duration::from_secs(10));
+		}
+		assert!(!scheduler.next_due.contains_key(&2));
+
+		scheduler.reschedule(1);
+		let due = scheduler.next_due.get(&1).copied().unwrap();
+		assert!(start + Duration::from_secs(200) <= due && due <= start + Duration::from_secs(210));
+	}
+
+	#[tokio::test(start_paused = true)]
+	async fn test_next_due_is_the_soonest() {
+		let mut scheduler = Scheduler::new(&sources());
+		scheduler.reschedule(0);
+		assert_eq!(scheduler.next_due().map(|(id, _)| id), Some(1));
+
+		scheduler.reschedule(1);
+		assert_eq!(scheduler.next_due().map(|(id, _)| id), Some(0));
+	}
+
+	#[tokio::test(start_paused = true)]
+	async fn test_removed_source_drops_its_turn() {
+		let mut scheduler = Scheduler::new(&sources());
+		scheduler.remove(0);
+		assert_eq!(scheduler.next_due().map(|(id, _)| id), Some(1));
+
+		// Removing a source which was rescheduled already drops its upcoming fetch as well
+		scheduler.reschedule(1);
+		scheduler.remove(1);
+		assert_eq!(scheduler.next_due(), None);
+		assert!(scheduler.timing.is_empty());
+	}
+}
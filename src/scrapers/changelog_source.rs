@@ -0,0 +1,245 @@
+use async_trait::async_trait;
+use scraper::{ElementRef, Html};
+
+use crate::embed::EmbedData;
+use crate::error::NewsError;
+use crate::json::sources::Source;
+use crate::scrapers::html_source::HtmlSource;
+use crate::scrapers::news_source::{ListedPage, NewsSource};
+use crate::scrapers::scrape_meta::scrape_meta;
+use crate::scrapers::scraper_resources::html_util::format_selector;
+
+// Sections listed at most in an embed, the remaining ones are only counted
+const SUMMARY_SECTIONS: usize = 10;
+
+// Headings are short, longer bold paragraphs are emphasized text
+const MAX_HEADING_LENGTH: usize = 80;
+
+// Characters starting a paragraph which is a single changelog item
+const ITEM_MARKERS: [char; 5] = ['-', '•', '–', '—', '*'];
+
+#[derive(Debug, Clone, Copy)]
+/// Reads changelogs like html sources, but summarizes their version and sections instead of posting a fixed preview
+pub struct ChangelogSource;
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+/// Structure of a single changelog page
+pub struct Changelog {
+	/// Version number of the client such as "2.21.1.21", if stated
+	pub version: Option<String>,
+	/// Release date as UTC timestamp, if stated
+	pub released: Option<i64>,
+	pub sections: Vec<ChangelogSection>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Heading of a changelog, such as "Ground vehicles", with the amount of changes listed below it
+pub struct ChangelogSection {
+	pub name: String,
+	pub items: usize,
+}
+
+#[async_trait]
+impl NewsSource for ChangelogSource {
+	async fn listed_page(&self, source: &Source, page_url: &str) -> Result<Option<ListedPage>, NewsError> {
+		HtmlSource.listed_page(source, page_url).await
+	}
+
+	fn normalise_url(&self, source: &Source, url: &str) -> String {
+		HtmlSource.normalise_url(source, url)
+	}
+
+	async fn embed_data(&self, source: &Source, url: &str) -> Result<EmbedData, NewsError> {
+		let selectors = HtmlSource::selectors(source)?;
+		HtmlSource::scrape_article(source, url, |html| {
+			let mut embed = scrape_meta(html, selectors, source.scrape_type, url)?;
+			let body = selectors.body.as_deref().unwrap_or("body");
+			let changelog = parse_changelog(html, body, &embed.title, embed.published)?;
+			embed.preview_text = changelog.summary();
+			embed.version = changelog.version;
+			Ok(embed)
+		}).await
+	}
+
+	fn validate(&self, source: &Source) -> Result<(), String> {
+		HtmlSource.validate(source)?;
+		match source.selectors.as_ref().and_then(|selectors| selectors.body.as_ref()) {
+			Some(_) => Ok(()),
+			None => Err("changelog sources require a body selector".to_owned()),
+		}
+	}
+}
+
+/// Reads the version from the title or content, and counts the items below each heading of the content
+pub fn parse_changelog(html: &Html, body_selector: &str, title: &str, released: Option<i64>) -> Result<Changelog, NewsError> {
+	let selector = format_selector(body_selector)?;
+	let Some(body) = html.select(&selector).next() else {
+		return Ok(Changelog {
+			version: find_version(title),
+			released,
+			sections: vec![],
+		});
+	};
+
+	let mut sections: Vec<ChangelogSection> = vec![];
+	for node in body.descendants() {
+		let Some(elem) = ElementRef::wrap(node) else {
+			continue;
+		};
+		// Sub-items and paragraphs inside items belong to their item
+		if elem.ancestors().filter_map(ElementRef::wrap).take_while(|ancestor| ancestor.id() != body.id()).any(|ancestor| ancestor.value().name() == "li") {
+			continue;
+		}
+
+		if let Some(name) = heading(elem) {
+			sections.push(ChangelogSection {
+				name,
+				items: 0,
+			});
+		} else if is_item(elem) {
+			match sections.last_mut() {
+				Some(section) => section.items += 1,
+				None => sections.push(ChangelogSection {
+					name: "General".to_owned(),
+					items: 1,
+				}),
+			}
+		}
+	}
+	// Headings grouping further headings list nothing themselves
+	sections.retain(|section| section.items > 0);
+
+	let text: String = body.text().collect();
+	Ok(Changelog {
+		version: find_version(title).or(find_version(&text)),
+		released,
+		sections,
+	})
+}
+
+/// Heading elements, or paragraphs consisting of bold text only
+fn heading(elem: ElementRef) -> Option<String> {
+	let text = collapsed_text(elem);
+	if text.is_empty() || text.len() > MAX_HEADING_LENGTH {
+		return None;
+	}
+	let is_heading = match elem.value().name() {
+		"h1" | "h2" | "h3" | "h4" | "h5" | "h6" => true,
+		"p" => {
+			let bold: String = elem.descendants()
+				.filter_map(ElementRef::wrap)
+				.filter(|child| matches!(child.value().name(), "strong" | "b"))
+				.map(collapsed_text)
+				.collect::<Vec<_>>()
+				.join(" ");
+			bold == text
+		}
+		_ => false,
+	};
+	is_heading.then(|| text.trim_end_matches(':').trim().to_owned())
+}
+
+fn is_item(elem: ElementRef) -> bool {
+	match elem.value().name() {
+		"li" => true,
+		"p" => collapsed_text(elem).starts_with(ITEM_MARKERS),
+		_ => false,
+	}
+}
+
+fn collapsed_text(elem: ElementRef) -> String {
+	elem.text().flat_map(str::split_whitespace).collect::<Vec<_>>().join(" ")
+}
+
+/// The first of the longest dotted numbers, such as "2.21" in "Update 2.21" or "2.21.1.21", dates are skipped
+pub fn find_version(text: &str) -> Option<String> {
+	let mut version: Option<&str> = None;
+	let candidates = text.split(|c: char| !(c.is_ascii_digit() || c == '.'))
+		.map(|candidate| candidate.trim_matches('.'))
+		.filter(|candidate| candidate.split('.').count() >= 2 && candidate.split('.').all(|part| !part.is_empty()))
+		.filter(|candidate| !is_date(candidate));
+	for candidate in candidates {
+		// Later versions are usually mentioned as the cause of a fix, so only longer ones replace the first
+		if version.map_or(true, |version| candidate.split('.').count() > version.split('.').count()) {
+			version = Some(candidate);
+		}
+	}
+	version.map(ToOwned::to_owned)
+}
+
+/// Dates such as "16.10.2022" or "2022.10.16"
+fn is_date(candidate: &str) -> bool {
+	let parts: Vec<&str> = candidate.split('.').collect();
+	parts.len() == 3 && (parts[0].len() == 4 || parts[2].len() == 4)
+}
+
+impl Changelog {
+	/// Lines for an embed, such as "Version 2.21" followed by "**Ground vehicles**: 12 changes"
+	pub fn summary(&self) -> String {
+		let mut lines = vec![];
+		if let Some(version) = &self.version {
+			lines.push(format!("Version {version}"));
+		}
+		if let Some(released) = self.released {
+			lines.push(format!("Released <t:{released}:D>"));
+		}
+		for section in self.sections.iter().take(SUMMARY_SECTIONS) {
+			let noun = if section.items == 1 { "change" } else { "changes" };
+			lines.push(format!("**{}**: {} {noun}", section.name, section.items));
+		}
+		if self.sections.len() > SUMMARY_SECTIONS {
+			let remaining: usize = self.sections[SUMMARY_SECTIONS..].iter().map(|section| section.items).sum();
+			lines.push(format!("and {remaining} changes in {} more sections", self.sections.len() - SUMMARY_SECTIONS));
+		}
+		if lines.is_empty() {
+			"The changelog could not be summarized, see the full changelog".to_owned()
+		} else {
+			lines.join("\n")
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use scraper::Html;
+
+	use crate::scrapers::changelog_source::{ChangelogSection, find_version, parse_changelog};
+
+	static RAW: &str = r#"<html><body><div class="content__body">
+		<p>Released with the update 2.21.1.21 for all platforms.</p>
+		<h2>Ground vehicles</h2>
+		<ul>
+			<li>Leopard 2A7V: fixed the turret rotation speed.</li>
+			<li>T-80BVM: corrected armor<ul><li>Hull</li><li>Turret</li></ul></li>
+		</ul>
+		<p><strong>Flight model:</strong></p>
+		<p>- Su-25: engine thrust reduced.</p>
+		<p>The changes below will follow later.</p>
+		<p><b>Interface</b></p>
+		<h3>Empty</h3>
+		<ul><li>Fixed the hangar</li></ul>
+	</div></body></html>"#;
+
+	#[test]
+	fn test_sections() {
+		let html = Html::parse_document(RAW);
+		let changelog = parse_changelog(&html, "div.content__body", "Update \"Hot Tracks\"", Some(0)).unwrap();
+		assert_eq!(changelog.version.as_deref(), Some("2.21.1.21"));
+		assert_eq!(changelog.sections, vec![
+			ChangelogSection { name: "Ground vehicles".to_owned(), items: 2 },
+			ChangelogSection { name: "Flight model".to_owned(), items: 1 },
+			ChangelogSection { name: "Empty".to_owned(), items: 1 },
+		]);
+		assert_eq!(changelog.summary(), "Version 2.21.1.21\nReleased <t:0:D>\n**Ground vehicles**: 2 changes\n**Flight model**: 1 change\n**Empty**: 1 change");
+	}
+
+	#[test]
+	fn test_version() {
+		assert_eq!(find_version("Update 2.21 \"Hot Tracks\""), Some("2.21".to_owned()));
+		assert_eq!(find_version("Changelog for 2.21.1.21."), Some("2.21.1.21".to_owned()));
+		assert_eq!(find_version("Update \"Kings of Battle\""), None);
+		assert_eq!(find_version("2.21.1.21 fixes a bug from 2.21.0.35"), Some("2.21.1.21".to_owned()));
+		assert_eq!(find_version("Update 2.21, released on 16.10.2022"), Some("2.21".to_owned()));
+		assert_eq!(find_version("Released on 2022.10.16"), None);
+	}
+}
\ No newline at end of file
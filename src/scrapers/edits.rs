@@ -0,0 +1,178 @@
+use std::collections::HashSet;
+
+use tracing::{error, warn};
+
+use crate::api::database::{Database, to_sqlite_integer};
+use crate::CONFIG;
+use crate::embed::EmbedData;
+use crate::error::NewsError;
+use crate::json::sources::Source;
+use crate::scrapers::events::record_event;
+use crate::scrapers::html_processing::get_embed_data;
+use crate::scrapers::news_source::SourceRegistry;
+use crate::scrapers::withdrawals::withdraw;
+
+// Changed passages quoted at most in a summary
+const QUOTED_PASSAGES: usize = 3;
+
+// Characters a quoted passage is cut down to
+const PASSAGE_LENGTH: usize = 150;
+
+/// Fetches recently delivered articles of the source again, and announces those which were edited since
+pub async fn recheck_articles(source: &Source, registry: &SourceRegistry, database: &Database, hooks: bool) {
+	let updates = &CONFIG.updates;
+	let now = chrono::Utc::now().timestamp();
+	let due = match database.get_due_rechecks(source.id, now - updates.window * 60 * 60, now - updates.interval * 60, updates.per_fetch).await {
+		Ok(due) => due,
+		Err(e) => {
+			error!("{e}");
+			return;
+		}
+	};
+
+	for stored in due {
+		let embed = match get_embed_data(&stored.url, source, registry).await {
+			Ok(embed) if !embed.is_fail_over() => embed,
+			Ok(_) => {
+				let _db_update_result = database.mark_checked(&stored.url, source.id).await;
+				continue;
+			}
+			// Pages serving "not found" would otherwise be announced as an edit
+			Err(NewsError::Gone(_)) => {
+				withdraw(source, registry, &stored.url, database, hooks).await;
+				continue;
+			}
+			Err(e) => {
+				warn!("Cannot check {} for edits: {e}", stored.url);
+				let _db_update_result = database.mark_checked(&stored.url, source.id).await;
+				continue;
+			}
+		};
+
+		// Articles without stored content, such as those seen while seeding, only get their first version stored
+		if let (Some(old), Some(title)) = (stored.fingerprint, &stored.title) {
+			if old != fingerprint(&embed) {
+				warn!("{} was edited", stored.url);
+				if hooks {
+					let old_content = stored.body.as_deref().filter(|body| !body.is_empty()).or(stored.preview.as_deref()).unwrap_or_default();
+					let summary = summarise(title, old_content, &embed);
+					if let Ok(news_source) = registry.resolve(source) {
+						embed.handle_notice("Updated", &summary, news_source, source, |notify| notify.updates).await;
+					}
+				}
+			}
+		}
+		remember(database, source.id, &embed).await;
+	}
+}
+
+/// Stores the current content of an article, which later checks compare against, and the event it announces
+pub async fn remember(database: &Database, source: u8, embed: &EmbedData) {
+	if embed.is_fail_over() {
+		return;
+	}
+	let _db_update_result = database.store_article(embed, source, fingerprint(embed)).await;
+	record_event(database, source, embed).await;
+}
+
+/// FNV-1a over the normalised title and content, so that whitespace or casing alone does not count as an edit
+pub fn fingerprint(embed: &EmbedData) -> i64 {
+	let text = format!("{}\n{}", normalise(&embed.title), normalise(content_of(embed)));
+	let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+	for byte in text.bytes() {
+		hash ^= u64::from(byte);
+		hash = hash.wrapping_mul(0x0100_0000_01b3);
+	}
+	to_sqlite_integer(hash)
+}
+
+/// The body, or the preview for sources which offer none
+fn content_of(embed: &EmbedData) -> &str {
+	if embed.body.is_empty() {
+		&embed.preview_text
+	} else {
+		&embed.body
+	}
+}
+
+fn normalise(text: &str) -> String {
+	text.split(|c: char| !c.is_alphanumeric())
+		.filter(|word| !word.is_empty())
+		.map(str::to_lowercase)
+		.collect::<Vec<_>>()
+		.join(" ")
+}
+
+/// Lines or sentences, the units changes are reported in
+fn passages(text: &str) -> impl Iterator<Item=&str> {
+	text.lines()
+		.flat_map(|line| line.split(". "))
+		.map(str::trim)
+		.filter(|passage| !normalise(passage).is_empty())
+}
+
+/// Describes what changed between two versions of an article in a few lines
+pub fn summarise(old_title: &str, old_content: &str, new: &EmbedData) -> String {
+	let mut lines = vec![];
+	if normalise(old_title) != normalise(&new.title) {
+		lines.push(format!("Title changed from \"{old_title}\""));
+	}
+
+	let old_passages: HashSet<String> = passages(old_content).map(normalise).collect();
+	let new_passages: HashSet<String> = passages(content_of(new)).map(normalise).collect();
+	let changed: Vec<&str> = passages(content_of(new))
+		.filter(|passage| !old_passages.contains(&normalise(passage)))
+		.collect();
+	let removed = old_passages.difference(&new_passages).count();
+
+	if !changed.is_empty() {
+		lines.push("New or changed:".to_owned());
+	}
+	for passage in changed.iter().take(QUOTED_PASSAGES) {
+		let mut quoted: String = passage.chars().take(PASSAGE_LENGTH).collect();
+		if quoted.len() < passage.len() {
+			quoted.push('…');
+		}
+		lines.push(format!("> {quoted}"));
+	}
+	if changed.len() > QUOTED_PASSAGES {
+		lines.push(format!("and {} more changed passages", changed.len() - QUOTED_PASSAGES));
+	}
+	if removed > 0 {
+		lines.push(format!("{removed} passages were removed"));
+	}
+
+	if lines.is_empty() {
+		"The article was edited".to_owned()
+	} else {
+		lines.join("\n")
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::embed::EmbedData;
+	use crate::scrapers::edits::{fingerprint, summarise};
+	use crate::scrapers::scraper_resources::resources::ScrapeType;
+
+	fn article(title: &str, body: &str) -> EmbedData {
+		let mut embed = EmbedData::new(title, "https://example.com/news/1", "", "Preview", ScrapeType::Main);
+		embed.body = body.to_owned();
+		embed
+	}
+
+	#[test]
+	fn test_cosmetic_changes_keep_the_fingerprint() {
+		let original = article("Event: Tank Week", "Rewards are available until the 5th.\nGood luck!");
+		assert_eq!(fingerprint(&original), fingerprint(&article("Event:  tank week", "Rewards are available until the 5th.  Good luck!")));
+		assert_ne!(fingerprint(&original), fingerprint(&article("Event: Tank Week", "Rewards are available until the 7th.\nGood luck!")));
+		// Sources without body are compared by their preview
+		assert_ne!(fingerprint(&article("A", "")), fingerprint(&article("B", "")));
+	}
+
+	#[test]
+	fn test_summary() {
+		let summary = summarise("Event: Tank Week", "Rewards are available until the 5th. Good luck!\nSee you", &article("Event: Tank Week (extended)", "Rewards are available until the 7th. Good luck!"));
+		assert_eq!(summary, "Title changed from \"Event: Tank Week\"\nNew or changed:\n> Rewards are available until the 7th\n2 passages were removed");
+	}
+}
\ No newline at end of file
@@ -0,0 +1,255 @@
+use chrono::{Datelike, DateTime, NaiveDate};
+use tracing::error;
+
+use crate::api::database::Database;
+use crate::embed::EmbedData;
+use crate::scrapers::scraper_resources::resources::ScrapeType;
+
+const MONTHS: [&str; 12] = ["january", "february", "march", "april", "may", "june", "july", "august", "september", "october", "november", "december"];
+
+// Hours ahead of UTC of the zones announcements state times in, times without a zone are in GMT
+const TIME_ZONES: [(&str, i64); 10] = [("GMT", 0), ("UTC", 0), ("BST", 1), ("CET", 1), ("CEST", 2), ("MSK", 3), ("EST", -5), ("EDT", -4), ("PST", -8), ("PDT", -7)];
+
+// Dates this long before the article was published refer to past events, such as a previous update
+const PAST_DATES: i64 = 60 * 60 * 24;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// When an event takes place, as UTC timestamps
+pub struct EventDates {
+	pub start: i64,
+	pub end: Option<i64>,
+	/// No time of day was stated, the event starts at midnight UTC
+	pub all_day: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// A date as written in prose, such as "October 14th at 11:00 GMT"
+struct Mention {
+	month: u32,
+	day: u32,
+	year: Option<i32>,
+	time: Option<(u32, u32)>,
+	offset: i64,
+}
+
+/// Stores the dates of the event a main news article announces, if it states any
+pub async fn record_event(database: &Database, source: u8, embed: &EmbedData) {
+	if embed.scrape_type != ScrapeType::Main {
+		return;
+	}
+	let content = if embed.body.is_empty() { &embed.preview_text } else { &embed.body };
+	let reference = match embed.published {
+		Some(published) => published,
+		// Rechecks happen once the event started, which must not drop its start date as being in the past
+		None => match database.get_fetch_date(&embed.url, source).await {
+			Ok(fetched) => fetched.unwrap_or_else(|| chrono::Utc::now().timestamp()),
+			Err(e) => {
+				error!("{e}");
+				return;
+			}
+		},
+	};
+	let Some(dates) = event_dates(&format!("{}\n{content}", embed.title), reference) else {
+		return;
+	};
+	if let Err(e) = database.store_event(source, &embed.url, &embed.title, &dates).await {
+		error!("{e}");
+	}
+}
+
+/// The first date of the text is the start, the first later one the end. Years which are not stated are the ones closest to the reference
+pub fn event_dates(text: &str, reference: i64) -> Option<EventDates> {
+	let mut stamps = mentions(&tokens(text)).into_iter()
+		.filter_map(|mention| Some((mention, resolve(&mention, reference)?)))
+		.filter(|(_, stamp)| *stamp >= reference - PAST_DATES);
+	let (first, start) = stamps.next()?;
+	Some(EventDates {
+		start,
+		end: stamps.map(|(_, stamp)| stamp).find(|stamp| *stamp > start),
+		all_day: first.time.is_none(),
+	})
+}
+
+/// Words, numbers and times, with dashes kept as their own token
+fn tokens(text: &str) -> Vec<&str> {
+	let mut tokens = vec![];
+	let mut start = None;
+	for (i, c) in text.char_indices() {
+		if c.is_alphanumeric() || c == ':' {
+			start.get_or_insert(i);
+			continue;
+		}
+		if let Some(start) = start.take() {
+			tokens.push(&text[start..i]);
+		}
+		if matches!(c, '-' | '–' | '—') {
+			tokens.push("-");
+		}
+	}
+	if let Some(start) = start {
+		tokens.push(&text[start..]);
+	}
+	tokens
+}
+
+fn mentions(tokens: &[&str]) -> Vec<Mention> {
+	let is_month = |token: &str| month(token).is_some();
+	let mut mentions = vec![];
+	for (i, token) in tokens.iter().enumerate() {
+		let Some(month) = month(token) else {
+			continue;
+		};
+		let before = |n: usize| i.checked_sub(n).map(|j| tokens[j]);
+		let after = |n: usize| tokens.get(i + n).copied();
+
+		// Days are either stated before the month, such as "14 October", "14th of October" or "14 - 17 October"
+		let mut days = vec![];
+		let mut next = i + 1;
+		if let Some(last) = before(1).and_then(day) {
+			if let (Some(first), Some(true), false) = (before(3).and_then(day), before(2).map(is_range), before(4).map_or(false, is_month)) {
+				days.push(first);
+			}
+			days.push(last);
+		} else if let (Some("of"), Some(first)) = (before(1), before(2).and_then(day)) {
+			days.push(first);
+		// ... or after it, such as "October 14th" or "October 14 - 17"
+		} else if let Some(first) = after(1).and_then(day) {
+			days.push(first);
+			next = i + 2;
+			if let (Some(true), Some(last), false) = (after(2).map(is_range), after(3).and_then(day), after(4).map_or(false, is_month)) {
+				days.push(last);
+				next = i + 4;
+			}
+		}
+		if days.is_empty() {
+			continue;
+		}
+
+		let year = tokens.get(next).copied().and_then(year);
+		if year.is_some() {
+			next += 1;
+		}
+		// Times follow closely, such as "at 11:00 GMT" or "(11:00 GMT)"
+		let mut time = None;
+		let mut offset = 0;
+		for (j, token) in tokens.iter().enumerate().skip(next).take(3) {
+			if is_month(token) {
+				break;
+			}
+			if let Some(time_of_day) = time_of_day(token) {
+				time = Some(time_of_day);
+				offset = tokens.get(j + 1).and_then(|zone| TIME_ZONES.iter().find(|(name, _)| name == zone)).map_or(0, |(_, offset)| *offset);
+				break;
+			}
+		}
+
+		mentions.extend(days.into_iter().map(|day| Mention {
+			month,
+			day,
+			year,
+			time,
+			offset,
+		}));
+	}
+	mentions
+}
+
+/// Capitalized month names or their abbreviations, such as "October" or "Oct"
+fn month(token: &str) -> Option<u32> {
+	if !token.starts_with(|c: char| c.is_uppercase()) || token.len() < 3 {
+		return None;
+	}
+	let token = token.to_lowercase();
+	MONTHS.iter()
+		.position(|month| *month == token || (token.len() <= 4 && month.starts_with(&token)))
+		.map(|position| position as u32 + 1)
+}
+
+fn day(token: &str) -> Option<u32> {
+	let number = token.trim_end_matches(['s', 't', 'n', 'd', 'r', 'h']);
+	number.parse().ok().filter(|day| (1..=31).contains(day) && number.len() <= 2)
+}
+
+fn year(token: &str) -> Option<i32> {
+	token.parse().ok().filter(|year| (2000..=2100).contains(year))
+}
+
+fn time_of_day(token: &str) -> Option<(u32, u32)> {
+	let (hours, minutes) = token.trim_end_matches(':').split_once(':')?;
+	let hours = hours.parse().ok().filter(|hours| *hours < 24)?;
+	let minutes = minutes.parse().ok().filter(|minutes| *minutes < 60)?;
+	Some((hours, minutes))
+}
+
+fn is_range(token: &str) -> bool {
+	matches!(token, "-" | "to" | "until" | "till")
+}
+
+fn resolve(mention: &Mention, reference: i64) -> Option<i64> {
+	let timestamp = |year: i32| {
+		let (hours, minutes) = mention.time.unwrap_or((0, 0));
+		let date = NaiveDate::from_ymd_opt(year, mention.month, mention.day)?.and_hms_opt(hours, minutes, 0)?;
+		Some(date.and_utc().timestamp() - mention.offset * 60 * 60)
+	};
+	if let Some(year) = mention.year {
+		return timestamp(year);
+	}
+	let reference_year = DateTime::from_timestamp(reference, 0)?.year();
+	(reference_year - 1..=reference_year + 1)
+		.filter_map(timestamp)
+		.min_by_key(|stamp| (stamp - reference).abs())
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::api::database::Database;
+	use crate::embed::EmbedData;
+	use crate::scrapers::events::{event_dates, EventDates, record_event};
+	use crate::scrapers::scraper_resources::resources::ScrapeType;
+
+	// 2022-10-10 12:00 UTC
+	const PUBLISHED: i64 = 1_665_403_200;
+
+	#[test]
+	fn test_event_dates() {
+		let text = "The event will start on October 14th at 11:00 GMT and will last until October 17th (11:00 GMT).";
+		assert_eq!(event_dates(text, PUBLISHED), Some(EventDates {
+			start: 1_665_745_200,
+			end: Some(1_666_004_400),
+			all_day: false,
+		}));
+
+		// Day ranges without a time, and years close to the publication
+		assert_eq!(event_dates("Discounts from 30 - 31 December!", PUBLISHED), Some(EventDates {
+			start: 1_672_358_400,
+			end: Some(1_672_444_800),
+			all_day: true,
+		}));
+
+		// Past dates, such as the release of the previous update, are no event
+		assert_eq!(event_dates("Since the 1st of September 2022, nothing happened", PUBLISHED), None);
+		assert_eq!(event_dates("Check out the new vehicles", PUBLISHED), None);
+	}
+
+	#[tokio::test]
+	async fn test_recheck_keeps_the_start_date() {
+		let path = std::env::temp_dir().join(format!("wt_event_handler_events_{}.sqlite", std::process::id()));
+		let db = Database::new(path.to_str().unwrap()).await.unwrap();
+
+		let url = "https://example.com/en/news/event";
+		db.store_recent([url], 0).await.unwrap();
+		sqlx::query("UPDATE sources SET fetch_date = ?").bind(PUBLISHED).execute(&db.connection).await.unwrap();
+
+		// Undated articles are checked again long after they were seen, here years after the event started
+		let embed = EmbedData::new("Event", url, "", "The event will start on October 14th at 11:00 GMT and will last until October 17th (11:00 GMT).", ScrapeType::Main);
+		record_event(&db, 0, &embed).await;
+		record_event(&db, 0, &embed).await;
+
+		let events = db.get_events(0).await.unwrap();
+		assert_eq!(events.len(), 1);
+		assert_eq!((events[0].start_date, events[0].end_date), (1_665_745_200, Some(1_666_004_400)));
+
+		drop(db);
+		drop(std::fs::remove_file(path));
+	}
+}
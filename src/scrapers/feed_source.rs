@@ -0,0 +1,108 @@
+use async_trait::async_trait;
+use feed_rs::model::Entry;
+
+use crate::embed::{EmbedData, EMPTY_IMG, PREVIEW_LENGTH};
+use crate::error::NewsError;
+use crate::json::sources::Source;
+use crate::scrapers::news_source::{ListedEmbeds, ListedPage, NewsSource};
+use crate::scrapers::scrape_meta::sanitize_html;
+use crate::scrapers::scraper_resources::resources::ScrapeType;
+
+#[derive(Debug, Default)]
+/// Reads articles from RSS or Atom feeds, which already carry all embed information
+pub struct FeedSource {
+	entries: ListedEmbeds,
+}
+
+#[async_trait]
+impl NewsSource for FeedSource {
+	async fn listed_page(&self, source: &Source, page_url: &str) -> Result<Option<ListedPage>, NewsError> {
+		self.entries.list_page(source, page_url, |raw| parse_feed(raw.as_bytes(), source.scrape_type)).await
+	}
+
+	fn normalise_url(&self, _source: &Source, url: &str) -> String {
+		url.to_owned()
+	}
+
+	async fn embed_data(&self, source: &Source, url: &str) -> Result<EmbedData, NewsError> {
+		self.entries.embed_data(source, url, |raw| parse_feed(raw.as_bytes(), source.scrape_type)).await
+	}
+
+	fn validate(&self, _source: &Source) -> Result<(), String> {
+		Ok(())
+	}
+}
+
+/// Parses RSS or Atom into embeds in the order the feed lists them, and the link to the next page of the feed
+pub fn parse_feed(raw: &[u8], scrape_type: ScrapeType) -> Result<(Vec<EmbedData>, Option<String>), NewsError> {
+	let feed = feed_rs::parser::parse(raw)?;
+	let entries = feed.entries.iter().filter_map(|entry| entry_to_embed(entry, scrape_type)).collect();
+	let next = feed.links.iter()
+		.find(|link| link.rel.as_deref() == Some("next"))
+		.map(|link| link.href.clone());
+	Ok((entries, next))
+}
+
+fn entry_to_embed(entry: &Entry, scrape_type: ScrapeType) -> Option<EmbedData> {
+	let link = entry.links.iter()
+		.find(|link| link.rel.as_deref().map_or(true, |rel| rel == "alternate"))
+		.or(entry.links.first())?;
+
+	let title = entry.title.as_ref().map_or(link.href.clone(), |title| title.content.clone());
+
+	let summary = entry.summary.as_ref().map(|summary| summary.content.clone())
+		.or(entry.media.iter().find_map(|media| media.description.as_ref().map(|description| description.content.clone())))
+		.or(entry.content.as_ref().and_then(|content| content.body.clone()))
+		.unwrap_or_default();
+	let preview_text: String = sanitize_html(summary.trim()).chars().take(PREVIEW_LENGTH).collect();
+
+	let mut embed = EmbedData::new(&title, &link.href, entry_image(entry).unwrap_or(EMPTY_IMG), &preview_text, scrape_type);
+	if let Some(body) = entry.content.as_ref().and_then(|content| content.body.as_ref()) {
+		embed.body = sanitize_html(body.trim());
+	}
+	embed.published = entry.published.or(entry.updated).map(|published| published.timestamp());
+	Some(embed)
+}
+
+/// Picks the first image enclosure or thumbnail
+fn entry_image(entry: &Entry) -> Option<&str> {
+	let enclosure = entry.media.iter()
+		.flat_map(|media| &media.content)
+		.filter(|content| content.content_type.as_ref().map_or(false, |mime| mime.to_string().starts_with("image/")))
+		.find_map(|content| content.url.as_ref().map(|url| url.as_str()));
+
+	enclosure.or(entry.media.iter()
+		.flat_map(|media| &media.thumbnails)
+		.map(|thumbnail| thumbnail.image.uri.as_str())
+		.next())
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::embed::EMPTY_IMG;
+	use crate::scrapers::feed_source::parse_feed;
+	use crate::scrapers::scraper_resources::resources::ScrapeType;
+
+	#[test]
+	fn test_rss_fixture() {
+		let (entries, next) = parse_feed(include_bytes!("../../assets/fixtures/rss.xml"), ScrapeType::Main).unwrap();
+		assert_eq!(next, None);
+		assert_eq!(entries.len(), 2);
+		assert_eq!(entries[0].title, "Development: Leopard 2A7V");
+		assert_eq!(entries[0].url, "https://example.com/blog/leopard-2a7v");
+		assert_eq!(entries[0].img_url, "https://example.com/img/leopard.jpg");
+		assert_eq!(entries[0].preview_text, "The latest Leopard joins the [German tree](https://example.com/tree) .");
+		// Audio enclosures are no image
+		assert_eq!(entries[1].img_url, EMPTY_IMG);
+	}
+
+	#[test]
+	fn test_atom_fixture() {
+		let (entries, next) = parse_feed(include_bytes!("../../assets/fixtures/atom.xml"), ScrapeType::Main).unwrap();
+		assert_eq!(next.as_deref(), Some("https://example.com/feed?page=2"));
+		assert_eq!(entries.len(), 1);
+		assert_eq!(entries[0].url, "https://example.com/watch?v=1");
+		assert_eq!(entries[0].img_url, "https://example.com/vi/1/hqdefault.jpg");
+		assert_eq!(entries[0].preview_text, "The next major update is coming!");
+	}
+}
\ No newline at end of file
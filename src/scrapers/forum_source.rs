@@ -0,0 +1,36 @@
+use async_trait::async_trait;
+
+use crate::embed::EmbedData;
+use crate::error::NewsError;
+use crate::json::sources::Source;
+use crate::json::webhooks::Hooks;
+use crate::scrapers::html_source::HtmlSource;
+use crate::scrapers::news_source::{ListedPage, NewsSource};
+use crate::webhook_handler::filter_forum;
+
+#[derive(Debug, Clone, Copy)]
+/// Reads forum sections like html sources, but lets the forum filters of webhooks decide over their topics
+pub struct ForumSource;
+
+#[async_trait]
+impl NewsSource for ForumSource {
+	async fn listed_page(&self, source: &Source, page_url: &str) -> Result<Option<ListedPage>, NewsError> {
+		HtmlSource.listed_page(source, page_url).await
+	}
+
+	fn normalise_url(&self, source: &Source, url: &str) -> String {
+		HtmlSource.normalise_url(source, url)
+	}
+
+	async fn embed_data(&self, source: &Source, url: &str) -> Result<EmbedData, NewsError> {
+		HtmlSource.embed_data(source, url).await
+	}
+
+	fn validate(&self, source: &Source) -> Result<(), String> {
+		HtmlSource.validate(source)
+	}
+
+	fn matches_filter(&self, _source: &Source, url: &str, hook: &Hooks) -> bool {
+		filter_forum(url, hook)
+	}
+}
@@ -1,15 +1,26 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use futures::stream::{self, StreamExt, TryStreamExt};
+use lazy_static::lazy_static;
+use tracing::warn;
+
 use crate::embed::EmbedData;
-use crate::error::{error_webhook, NewsError};
+use crate::error::NewsError;
 use crate::json::sources::Source;
-use crate::scrapers::scrape_meta::scrape_meta;
-use crate::scrapers::scraper_resources::resources::{format_into_final_url, get_listed_links, request_html, ScrapeType};
+use crate::scrapers::news_source::{ListedPage, SourceRegistry};
+use crate::scrapers::scraper_resources::resources::{forget_validators, keep_validators};
 
-/// Returns all embeds for new news posts
-pub async fn html_processor(source: &Source) -> Result<Vec<EmbedData>, NewsError> {
-	let scrape_type = source.scrape_type;
+lazy_static! {
+	/// Last listing returned per page URL, reused when the page did not change
+	static ref LISTED_PAGES: Mutex<HashMap<String, ListedPage>> = Mutex::new(HashMap::new());
+}
 
-	let mut links = scrape_links(source).await?;
+// Articles of a single source fetched at the same time, domains are limited further by the politeness rules
+const ARTICLE_WORKERS: usize = 4;
 
+/// Returns all embeds for new news posts among the listed links, links which were taken down already are only remembered as known
+pub async fn html_processor(source: &mut Source, registry: &SourceRegistry, mut links: Vec<String>) -> Result<Vec<EmbedData>, NewsError> {
 	// Removes already known URLs
 	let mut positions = vec![];
 	for (position, link) in links.iter().enumerate() {
@@ -24,34 +35,216 @@ pub async fn html_processor(source: &Source) -> Result<Vec<EmbedData>, NewsError
 	}
 
 
-	let mut final_embeds = vec![];
-	for link in links {
-		let embed = get_embed_data(&link, scrape_type).await?;
-		final_embeds.push(embed);
-	}
+	// Listings start with the newest article, but catching up should deliver the oldest first
+	let embeds: Vec<Result<EmbedData, String>> = stream::iter(links.into_iter().rev())
+		.map(|link| {
+			let source = &*source;
+			async move {
+				match get_embed_data(&link, source, registry).await {
+					// Articles taken down right after being listed are not worth delivering
+					Err(NewsError::Gone(url)) => {
+						warn!("{url} is listed, but was taken down");
+						Ok(Err(url))
+					}
+					result => result.map(Ok),
+				}
+			}
+		})
+		.buffered(ARTICLE_WORKERS)
+		.try_collect()
+		.await?;
 
-	Ok(final_embeds)
+	let (embeds, gone): (Vec<_>, Vec<_>) = embeds.into_iter().partition(Result::is_ok);
+	// Otherwise every fetch would request them again for as long as they stay listed
+	source.store_recent(gone.into_iter().filter_map(Result::err));
+	Ok(embeds.into_iter().filter_map(Result::ok).collect())
 }
 
 /// Returns embed-ready information per URL source
-pub async fn get_embed_data(url: &str, scrape_type: ScrapeType) -> Result<EmbedData, NewsError> {
-	let post_html = request_html(url).await?;
-	Ok(match scrape_meta(&post_html, scrape_type, url) {
-		Ok(ok) => ok,
-		Err(e) => {
-			error_webhook(&e, "", true).await;
-			EmbedData::fail_over(url, scrape_type)
-		}
-	})
+pub async fn get_embed_data(url: &str, source: &Source, registry: &SourceRegistry) -> Result<EmbedData, NewsError> {
+	let mut embed = registry.resolve(source)?.embed_data(source, url).await?;
+	embed.language = source.language.clone();
+	Ok(embed)
 }
 
-/// Returns all URLs per channel
-pub async fn scrape_links(channel: &Source) -> Result<Vec<String>, NewsError> {
-	let html = request_html(&channel.domain).await?;
+/// Returns all URLs per channel, following further pages until an already known URL shows up or the listing is older than `max_age`
+pub async fn scrape_links(channel: &Source, registry: &SourceRegistry) -> Result<Vec<String>, NewsError> {
+	let news_source = registry.resolve(channel)?;
+
+	// Without any known URL there is nothing to catch up on, and every page would be new
+	let max_pages = if channel.tracked_urls.is_empty() {
+		1
+	} else {
+		channel.max_pages
+	};
+
+	let cutoff = channel.max_age_cutoff(chrono::Utc::now().timestamp());
+	let mut urls: Vec<String> = vec![];
+	let mut page_url = channel.domain.clone();
+	for _ in 0..max_pages {
+		// A 304 would leave nothing to list without the listing it refers to
+		if !LISTED_PAGES.lock().unwrap().contains_key(&page_url) {
+			forget_validators(&page_url);
+		}
+
+		// Unchanged pages list exactly what they listed last time, without being parsed again
+		let page = match news_source.listed_page(channel, &page_url).await? {
+			Some(page) => {
+				LISTED_PAGES.lock().unwrap().insert(page_url.clone(), page.clone());
+				keep_validators(&page_url);
+				page
+			}
+			None => {
+				LISTED_PAGES.lock().unwrap().get(&page_url).cloned().unwrap_or_default()
+			}
+		};
+
+		// Pinned articles may be listed on every page, and known ones on top of a page say nothing about the articles below
+		let mut oldest = None;
+		for url in page.links {
+			let url = news_source.normalise_url(channel, &url);
+			if !urls.contains(&url) {
+				oldest = Some(url.clone());
+				urls.push(url);
+			}
+		}
+		let reached_known = oldest.map_or(false, |oldest| !channel.is_new(&oldest));
+		// Further pages only list even older articles, which would not be delivered
+		let reached_max_age = page.oldest_published.map_or(false, |published| published < cutoff);
 
-	let mut urls = get_listed_links(channel.scrape_type, &html)?;
-	for url in &mut urls {
-		*url = format_into_final_url(url, channel.scrape_type);
+		match page.next {
+			Some(next) if !reached_known && !reached_max_age => {
+				page_url = news_source.normalise_url(channel, &next);
+			}
+			_ => {
+				break;
+			}
+		}
 	}
 	Ok(urls)
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+	use std::collections::HashMap;
+
+	use async_trait::async_trait;
+
+	use crate::embed::EmbedData;
+	use crate::error::NewsError;
+	use crate::json::sources::{Source, Sources};
+	use crate::scrapers::html_processing::{html_processor, scrape_links};
+	use crate::scrapers::news_source::{ListedPage, NewsSource, SourceRegistry};
+
+	#[derive(Debug)]
+	/// Serves fixed listing pages by their URL, articles whose link starts with "gone" were taken down
+	struct PagedListing {
+		pages: HashMap<String, ListedPage>,
+	}
+
+	#[async_trait]
+	impl NewsSource for PagedListing {
+		async fn listed_page(&self, _source: &Source, page_url: &str) -> Result<Option<ListedPage>, NewsError> {
+			Ok(self.pages.get(page_url).cloned())
+		}
+
+		fn normalise_url(&self, _source: &Source, url: &str) -> String {
+			url.to_owned()
+		}
+
+		async fn embed_data(&self, source: &Source, url: &str) -> Result<EmbedData, NewsError> {
+			if url.starts_with("gone") {
+				return Err(NewsError::Gone(url.to_owned()));
+			}
+			Ok(EmbedData::fail_over(url, source.scrape_type))
+		}
+
+		fn validate(&self, _source: &Source) -> Result<(), String> {
+			Ok(())
+		}
+	}
+
+	fn page(links: &[&str], next: Option<&str>) -> ListedPage {
+		ListedPage {
+			links: links.iter().map(|link| (*link).to_owned()).collect(),
+			next: next.map(ToOwned::to_owned),
+			oldest_published: None,
+		}
+	}
+
+	/// A single source of the paged kind, which catches up on articles of the last 24 hours
+	fn paged_source(registry: &SourceRegistry) -> Source {
+		let raw = br#"{"sources": [
+			{"name": "paged", "domain": "https://paged.example/1", "id": 0, "scrape_type": "Main", "kind": "paged", "max_pages": 5, "max_age": 24}
+		]}"#;
+		Sources::from_slice(raw, registry).unwrap().sources.remove(0)
+	}
+
+	#[tokio::test]
+	async fn test_pagination_stops_at_known_articles() {
+		let listing = PagedListing {
+			pages: [
+				// The pinned article is known and listed on top of every page
+				("https://paged.example/1", page(&["pinned", "a", "b"], Some("https://paged.example/2"))),
+				("https://paged.example/2", page(&["pinned", "c", "d"], Some("https://paged.example/3"))),
+				("https://paged.example/3", page(&["pinned", "e", "f"], Some("https://paged.example/4"))),
+				("https://paged.example/4", page(&["pinned", "g"], None)),
+			].into_iter().map(|(url, page)| (url.to_owned(), page)).collect(),
+		};
+		let mut registry = SourceRegistry::empty();
+		registry.register("paged", listing);
+
+		let mut source = paged_source(&registry);
+		source.store_recent(["pinned", "d"]);
+
+		// Page 2 ends with a known article, so page 3 is not read
+		assert_eq!(scrape_links(&source, &registry).await.unwrap(), vec!["pinned", "a", "b", "c", "d"]);
+
+		source.tracked_urls.remove("d");
+		source.store_recent(["f"]);
+		assert_eq!(scrape_links(&source, &registry).await.unwrap(), vec!["pinned", "a", "b", "c", "d", "e", "f"]);
+	}
+
+	#[tokio::test]
+	async fn test_pagination_stops_at_max_age() {
+		let now = chrono::Utc::now().timestamp();
+		let dated = |links: &[&str], next: Option<&str>, hours_ago: i64| ListedPage {
+			oldest_published: Some(now - hours_ago * 60 * 60),
+			..page(links, next)
+		};
+		let listing = PagedListing {
+			pages: [
+				("https://paged.example/1", dated(&["a", "b"], Some("https://paged.example/2"), 12)),
+				("https://paged.example/2", dated(&["c", "d"], Some("https://paged.example/3"), 36)),
+				("https://paged.example/3", dated(&["e", "f"], None, 60)),
+			].into_iter().map(|(url, page)| (url.to_owned(), page)).collect(),
+		};
+		let mut registry = SourceRegistry::empty();
+		registry.register("paged", listing);
+
+		let mut source = paged_source(&registry);
+		source.store_recent(["known"]);
+
+		// Page 2 already lists articles older than 24 hours, so page 3 is not read
+		assert_eq!(scrape_links(&source, &registry).await.unwrap(), vec!["a", "b", "c", "d"]);
+
+		source.max_age = 48;
+		assert_eq!(scrape_links(&source, &registry).await.unwrap(), vec!["a", "b", "c", "d", "e", "f"]);
+	}
+
+	#[tokio::test]
+	async fn test_taken_down_articles_are_not_fetched_again() {
+		let mut registry = SourceRegistry::empty();
+		registry.register("paged", PagedListing {
+			pages: HashMap::new(),
+		});
+		let mut source = paged_source(&registry);
+		source.store_recent(["known"]);
+
+		let listed = vec!["a".to_owned(), "gone".to_owned(), "known".to_owned()];
+		let embeds = html_processor(&mut source, &registry, listed).await.unwrap();
+		assert_eq!(embeds.iter().map(|embed| embed.url.as_str()).collect::<Vec<_>>(), vec!["a"]);
+		// Known links are skipped, so the next fetch does not request it again
+		assert!(!source.is_new("gone"));
+	}
+}
@@ -0,0 +1,94 @@
+use async_trait::async_trait;
+use scraper::Html;
+
+use crate::embed::EmbedData;
+use crate::error::{error_webhook, NewsError};
+use crate::json::selectors::{AuthorSelectors, MetaSelector, Selectors};
+use crate::json::sources::Source;
+use crate::scrapers::news_source::{ListedPage, NewsSource};
+use crate::scrapers::scrape_meta::scrape_meta;
+use crate::scrapers::scraper_resources::html_util::format_selector;
+use crate::scrapers::scraper_resources::resources::{get_listed_links, request_html, request_text_if_modified};
+
+#[derive(Debug, Clone, Copy)]
+/// Scrapes listings and articles from HTML pages as described by the sources selectors
+pub struct HtmlSource;
+
+impl HtmlSource {
+	pub fn selectors(source: &Source) -> Result<&Selectors, NewsError> {
+		source.selectors.as_ref()
+			.ok_or(NewsError::BadSourceConfig(format!("source {} has no selectors", source.name)))
+	}
+
+	/// Fetches an article and reads its embed from the parsed page, falling back to the fail-over embed when `scrape` fails
+	pub async fn scrape_article<F>(source: &Source, url: &str, scrape: F) -> Result<EmbedData, NewsError>
+		where F: FnOnce(&Html) -> Result<EmbedData, NewsError> + Send
+	{
+		// The parsed document is not Send, so it may not live across the await below
+		let meta = {
+			let post_html = request_html(url).await?;
+			scrape(&post_html)
+		};
+		Ok(match meta {
+			Ok(ok) => ok,
+			Err(e) => {
+				error_webhook(&e, "", true).await;
+				EmbedData::fail_over(url, source.scrape_type)
+			}
+		})
+	}
+}
+
+#[async_trait]
+impl NewsSource for HtmlSource {
+	async fn listed_page(&self, source: &Source, page_url: &str) -> Result<Option<ListedPage>, NewsError> {
+		let selectors = Self::selectors(source)?;
+		let Some(text) = request_text_if_modified(page_url).await? else {
+			return Ok(None);
+		};
+		let html = Html::parse_document(&text);
+
+		let next = match &selectors.next_page {
+			Some(next_page) => {
+				let next_page = format_selector(next_page)?;
+				html.select(&next_page)
+					.find_map(|elem| elem.value().attr("href"))
+					.map(ToOwned::to_owned)
+			}
+			None => None,
+		};
+
+		Ok(Some(ListedPage {
+			links: get_listed_links(selectors, page_url, &html)?,
+			next,
+			oldest_published: None,
+		}))
+	}
+
+	fn normalise_url(&self, source: &Source, url: &str) -> String {
+		match &source.selectors {
+			Some(selectors) => selectors.format_into_final_url(url),
+			None => url.to_owned(),
+		}
+	}
+
+	async fn embed_data(&self, source: &Source, url: &str) -> Result<EmbedData, NewsError> {
+		let selectors = Self::selectors(source)?;
+		Self::scrape_article(source, url, |html| scrape_meta(html, selectors, source.scrape_type, url)).await
+	}
+
+	fn validate(&self, source: &Source) -> Result<(), String> {
+		let selectors = source.selectors.as_ref().ok_or("html sources require selectors")?;
+
+		// Parses every selector once, so that typos are caught before the first fetch
+		let metas = [&selectors.title, &selectors.preview, &selectors.image].into_iter().chain(&selectors.published);
+		let meta_selectors = metas.flat_map(MetaSelector::css_selectors).chain(selectors.author.iter().flat_map(AuthorSelectors::css_selectors));
+		let listing_selectors = [&selectors.listing, &selectors.link].into_iter().chain(&selectors.next_page).chain(&selectors.body).map(String::as_str);
+		for selector in listing_selectors.chain(meta_selectors) {
+			if format_selector(selector).is_err() {
+				return Err(format!("invalid selector \'{selector}\'"));
+			}
+		}
+		Ok(())
+	}
+}
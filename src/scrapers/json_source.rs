@@ -0,0 +1,146 @@
+use async_trait::async_trait;
+use serde_json::Value;
+
+use crate::embed::{EmbedData, EMPTY_IMG, PREVIEW_LENGTH};
+use crate::error::NewsError;
+use crate::json::selectors::JsonFields;
+use crate::json::sources::Source;
+use crate::scrapers::news_source::{ListedEmbeds, ListedPage, NewsSource};
+use crate::scrapers::scrape_meta::sanitize_html;
+use crate::scrapers::scraper_resources::dates::parse_date;
+use crate::scrapers::scraper_resources::resources::ScrapeType;
+
+#[derive(Debug, Default)]
+/// Reads articles from JSON endpoints as described by the sources field paths
+pub struct JsonSource {
+	entries: ListedEmbeds,
+}
+
+impl JsonSource {
+	fn fields(source: &Source) -> Result<&JsonFields, NewsError> {
+		source.json.as_ref()
+			.ok_or(NewsError::BadSourceConfig(format!("source {} has no json fields", source.name)))
+	}
+}
+
+#[async_trait]
+impl NewsSource for JsonSource {
+	async fn listed_page(&self, source: &Source, page_url: &str) -> Result<Option<ListedPage>, NewsError> {
+		let fields = Self::fields(source)?;
+		self.entries.list_page(source, page_url, |raw| parse_json(raw, fields, source.scrape_type, page_url)).await
+	}
+
+	fn normalise_url(&self, source: &Source, url: &str) -> String {
+		match &source.json {
+			Some(fields) => fields.format_into_final_url(url),
+			None => url.to_owned(),
+		}
+	}
+
+	async fn embed_data(&self, source: &Source, url: &str) -> Result<EmbedData, NewsError> {
+		let fields = Self::fields(source)?;
+		self.entries.embed_data(source, url, |raw| parse_json(raw, fields, source.scrape_type, &source.domain)).await
+	}
+
+	fn validate(&self, source: &Source) -> Result<(), String> {
+		let fields = source.json.as_ref().ok_or("json sources require json fields")?;
+		if fields.url.is_empty() || fields.title.is_empty() {
+			return Err("json fields require an url and title path".to_owned());
+		}
+		Ok(())
+	}
+}
+
+/// Parses all articles out of a JSON document in the order they are listed, and the link to the next page
+pub fn parse_json(raw: &str, fields: &JsonFields, scrape_type: ScrapeType, domain: &str) -> Result<(Vec<EmbedData>, Option<String>), NewsError> {
+	let document: Value = serde_json::from_str(raw)?;
+	let items = select_path(&document, &fields.items)
+		.and_then(Value::as_array)
+		.ok_or(NewsError::SelectedNothing(fields.items.clone(), domain.to_owned()))?;
+
+	let mut res = vec![];
+	for item in items {
+		// Articles without link cannot be tracked
+		let Some(url) = select_string(item, &fields.url) else {
+			continue;
+		};
+		let url = fields.format_into_final_url(&url);
+
+		let title = select_string(item, &fields.title).unwrap_or(url.clone());
+		let preview_text: String = fields.preview.as_ref()
+			.and_then(|path| select_string(item, path))
+			.map(|preview| sanitize_html(preview.trim()).chars().take(PREVIEW_LENGTH).collect())
+			.unwrap_or_default();
+		let img_url = fields.image.as_ref()
+			.and_then(|path| select_string(item, path))
+			.unwrap_or(EMPTY_IMG.to_owned());
+
+		let mut embed = EmbedData::new(&title, &url, &img_url, &preview_text, scrape_type);
+		if let Some(body) = fields.body.as_ref().and_then(|path| select_string(item, path)) {
+			embed.body = sanitize_html(body.trim());
+		}
+		embed.published = fields.published.as_ref()
+			.and_then(|path| select_string(item, path))
+			.and_then(|published| parse_date(&published));
+		res.push(embed);
+	}
+
+	let next = fields.next.as_ref().and_then(|path| select_string(&document, path));
+	Ok((res, next))
+}
+
+/// Walks a dot-separated path of keys or array indices, an empty path returns the value itself
+fn select_path<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+	path.split('.')
+		.filter(|segment| !segment.is_empty())
+		.try_fold(value, |current, segment| match current {
+			Value::Array(array) => array.get(segment.parse::<usize>().ok()?),
+			_ => current.get(segment),
+		})
+}
+
+fn select_string(value: &Value, path: &str) -> Option<String> {
+	match select_path(value, path)? {
+		Value::String(string) => Some(string.clone()),
+		Value::Number(number) => Some(number.to_string()),
+		_ => None,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::embed::EMPTY_IMG;
+	use crate::json::selectors::JsonFields;
+	use crate::scrapers::json_source::parse_json;
+	use crate::scrapers::scraper_resources::resources::ScrapeType;
+
+	#[test]
+	fn test_field_paths() {
+		static RAW: &str = r#"{"next": "/api/news?page=2", "data": {"items": [
+			{"path": "/en/news/1-event", "meta": {"title": "Event"}, "summary": "Starts today", "images": [{"src": "https://example.com/1.jpg"}]},
+			{"meta": {"title": "No link"}},
+			{"path": "https://example.com/2", "meta": {"title": "Second"}}
+		]}}"#;
+		let fields = JsonFields {
+			items: "data.items".to_owned(),
+			url: "path".to_owned(),
+			title: "meta.title".to_owned(),
+			preview: Some("summary".to_owned()),
+			image: Some("images.0.src".to_owned()),
+			body: None,
+			published: None,
+			base_url: Some("https://warthunder.com".to_owned()),
+			next: Some("next".to_owned()),
+		};
+
+		let (entries, next) = parse_json(RAW, &fields, ScrapeType::Main, "").unwrap();
+		assert_eq!(next.as_deref(), Some("/api/news?page=2"));
+		assert_eq!(entries.len(), 2);
+		assert_eq!(entries[0].url, "https://warthunder.com/en/news/1-event");
+		assert_eq!(entries[0].title, "Event");
+		assert_eq!(entries[0].preview_text, "Starts today");
+		assert_eq!(entries[0].img_url, "https://example.com/1.jpg");
+		assert_eq!(entries[1].url, "https://example.com/2");
+		assert_eq!(entries[1].img_url, EMPTY_IMG);
+	}
+}
\ No newline at end of file
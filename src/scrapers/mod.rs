@@ -1,3 +1,13 @@
 pub mod scraper_resources;
 pub mod html_processing;
-mod scrape_meta;
\ No newline at end of file
+pub mod news_source;
+pub mod html_source;
+pub mod feed_source;
+pub mod json_source;
+pub mod changelog_source;
+pub mod forum_source;
+mod scrape_meta;
+pub mod edits;
+pub mod withdrawals;
+pub mod versions;
+pub mod events;
\ No newline at end of file
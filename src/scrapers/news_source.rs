@@ -0,0 +1,155 @@
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+
+use crate::embed::EmbedData;
+use crate::error::NewsError;
+use crate::json::sources::Source;
+use crate::json::webhooks::Hooks;
+use crate::scrapers::changelog_source::ChangelogSource;
+use crate::scrapers::feed_source::FeedSource;
+use crate::scrapers::forum_source::ForumSource;
+use crate::scrapers::html_source::HtmlSource;
+use crate::scrapers::json_source::JsonSource;
+use crate::scrapers::scraper_resources::resources::{request_text, request_text_if_modified};
+use crate::webhook_handler::filter_main;
+
+#[async_trait]
+/// Implemented by every kind of source the fetch loop can drive
+pub trait NewsSource: Send + Sync + Debug {
+	/// Returns every article link listed on a single page of the source as found on the page,
+	/// or None when the page did not change since it was last listed
+	async fn listed_page(&self, source: &Source, page_url: &str) -> Result<Option<ListedPage>, NewsError>;
+
+	/// Turns a listed link into the absolute URL used for tracking and posting
+	fn normalise_url(&self, source: &Source, url: &str) -> String;
+
+	/// Collects embed information for a single article
+	async fn embed_data(&self, source: &Source, url: &str) -> Result<EmbedData, NewsError>;
+
+	/// Checks the source definition for everything this kind relies on, returns the reason when it does not fit
+	fn validate(&self, source: &Source) -> Result<(), String>;
+
+	/// Whether the keyword filters of a webhook let an article of the source through, the main filter decides by default
+	fn matches_filter(&self, _source: &Source, url: &str, hook: &Hooks) -> bool {
+		filter_main(url, hook)
+	}
+}
+
+#[derive(Clone, Debug, Default)]
+/// One page of a sources listing
+pub struct ListedPage {
+	pub links: Vec<String>,
+	/// Link to the following page with older articles, if the source has one
+	pub next: Option<String>,
+	/// Publish date of the oldest article on the page as UTC timestamp, if the listing states dates
+	pub oldest_published: Option<i64>,
+}
+
+#[derive(Clone, Debug)]
+/// Maps the `kind` of a source definition to its implementation
+pub struct SourceRegistry {
+	kinds: HashMap<String, Arc<dyn NewsSource>>,
+}
+
+impl SourceRegistry {
+	pub fn empty() -> Self {
+		Self {
+			kinds: HashMap::new(),
+		}
+	}
+
+	/// Registers or replaces the implementation used for sources of this kind
+	pub fn register(&mut self, kind: &str, implementation: impl NewsSource + 'static) -> &mut Self {
+		self.kinds.insert(kind.to_owned(), Arc::new(implementation));
+		self
+	}
+
+	pub fn get(&self, kind: &str) -> Option<&dyn NewsSource> {
+		self.kinds.get(kind).map(AsRef::as_ref)
+	}
+
+	pub fn resolve(&self, source: &Source) -> Result<&dyn NewsSource, NewsError> {
+		self.get(&source.kind)
+			.ok_or(NewsError::BadSourceConfig(format!("source {} uses the unknown kind \'{}\'", source.name, source.kind)))
+	}
+}
+
+#[derive(Debug, Default)]
+/// Keeps the embeds of sources whose listing already carries all article information, so articles need no extra request
+pub struct ListedEmbeds {
+	/// Embeds of the last listing per source ID and article URL
+	entries: Mutex<HashMap<u8, HashMap<String, EmbedData>>>,
+}
+
+impl ListedEmbeds {
+	/// Stores a listed page, the first page of a listing replaces everything known about this source
+	pub fn store(&self, source_id: u8, embeds: Vec<EmbedData>, first_page: bool) {
+		let by_url = embeds.into_iter().map(|embed| (embed.url.clone(), embed));
+		let mut entries = self.entries.lock().unwrap();
+		let source_entries = entries.entry(source_id).or_default();
+		if first_page {
+			source_entries.clear();
+		}
+		source_entries.extend(by_url);
+	}
+
+	pub fn get(&self, source_id: u8, url: &str) -> Option<EmbedData> {
+		self.entries.lock().unwrap()
+			.get(&source_id)
+			.and_then(|entries| entries.get(url))
+			.cloned()
+	}
+
+	/// Lists a page whose document `parse` turns into its embeds and the link to the next page
+	pub async fn list_page<F>(&self, source: &Source, page_url: &str, parse: F) -> Result<Option<ListedPage>, NewsError>
+		where F: Fn(&str) -> Result<(Vec<EmbedData>, Option<String>), NewsError> + Send
+	{
+		let Some(raw) = request_text_if_modified(page_url).await? else {
+			return Ok(None);
+		};
+		let (embeds, next) = parse(&raw)?;
+		Ok(Some(self.store_page(source, page_url, embeds, next)))
+	}
+
+	/// Embed of a listed article, reading the first page of the source again when the article is not known
+	pub async fn embed_data<F>(&self, source: &Source, url: &str, parse: F) -> Result<EmbedData, NewsError>
+		where F: Fn(&str) -> Result<(Vec<EmbedData>, Option<String>), NewsError> + Send
+	{
+		if let Some(entry) = self.get(source.id, url) {
+			return Ok(entry);
+		}
+
+		// Articles posted manually or through the API were never listed before, a 304 would not list them either
+		let (embeds, next) = parse(&request_text(&source.domain).await?)?;
+		self.store_page(source, &source.domain, embeds, next);
+		self.get(source.id, url)
+			.ok_or(NewsError::MetaCannotBeScraped(source.scrape_type, url.to_owned()))
+	}
+
+	fn store_page(&self, source: &Source, page_url: &str, embeds: Vec<EmbedData>, next: Option<String>) -> ListedPage {
+		let links = embeds.iter().map(|embed| embed.url.clone()).collect();
+		let oldest_published = embeds.iter().filter_map(|embed| embed.published).min();
+		self.store(source.id, embeds, page_url == source.domain);
+		ListedPage {
+			links,
+			next,
+			oldest_published,
+		}
+	}
+}
+
+impl Default for SourceRegistry {
+	/// Contains all kinds shipped with the handler
+	fn default() -> Self {
+		let mut registry = Self::empty();
+		registry.register("html", HtmlSource);
+		registry.register("feed", FeedSource::default());
+		registry.register("json", JsonSource::default());
+		registry.register("changelog", ChangelogSource);
+		registry.register("forum", ForumSource);
+		registry
+	}
+}
@@ -1,49 +1,150 @@
-use scraper::Html;
+use scraper::{ElementRef, Html};
 
-use crate::embed::{EmbedData, EMPTY_IMG};
+use crate::embed::{Author, EmbedData, EMPTY_IMG, PREVIEW_LENGTH};
 use crate::error::NewsError;
-use crate::scrapers::scraper_resources::html_util::{ElemUtil, format_selector, HtmlUtil};
+use crate::json::selectors::{AuthorSelectors, MetaSelector, Selectors};
+use crate::scrapers::scraper_resources::html_util::format_selector;
+use crate::scrapers::scraper_resources::dates::parse_date;
 use crate::scrapers::scraper_resources::resources::ScrapeType;
 
+// Elements ending a line of the article text
+const BLOCK_ELEMENTS: [&str; 15] = ["p", "div", "br", "li", "tr", "h1", "h2", "h3", "h4", "h5", "h6", "blockquote", "ul", "ol", "table"];
+
+// Elements whose content is no text of the article
+const HIDDEN_ELEMENTS: [&str; 3] = ["script", "style", "noscript"];
+
 /// Collects embed information from page
-pub fn scrape_meta(html: &Html, scrape_type: ScrapeType, post_url: &str) -> Result<EmbedData, NewsError> {
-	let (title, img_url, preview_text) = match scrape_type {
-		ScrapeType::Forum => {
-			let title_elem = html.select_first("head>meta:nth-child(5)", post_url)?;
-			let preview_elem = html.select_first("head>meta:nth-child(8)", post_url)?;
-			(
-				title_elem.select_attribute("content", post_url)?,
-				String::new(),
-				preview_elem.select_attribute("content", post_url)?
-			)
+pub fn scrape_meta(html: &Html, selectors: &Selectors, scrape_type: ScrapeType, post_url: &str) -> Result<EmbedData, NewsError> {
+	let title = resolve_meta(html, &selectors.title, scrape_type, post_url)?;
+	let preview_text = resolve_meta(html, &selectors.preview, scrape_type, post_url)?;
+	let img_url = resolve_meta(html, &selectors.image, scrape_type, post_url).unwrap_or(EMPTY_IMG.to_owned());
+
+	let mut embed = EmbedData::new(&title, post_url, &img_url, &preview_text, scrape_type);
+	if let Some(body) = &selectors.body {
+		embed.body = article_text(html, body)?;
+	}
+	// Articles are still worth delivering without a date
+	if let Some(published) = &selectors.published {
+		embed.published = resolve_meta(html, published, scrape_type, post_url).ok().and_then(|date| parse_date(&date));
+	}
+	if let Some(author) = &selectors.author {
+		embed.author = resolve_author(html, author);
+	}
+	Ok(embed)
+}
+
+/// The author of the first matching post, articles are still worth delivering without one
+fn resolve_author(html: &Html, selectors: &AuthorSelectors) -> Option<Author> {
+	let post = html.select(&format_selector(&selectors.post).ok()?).next()?;
+	let first = |selector: &str| format_selector(selector).ok().and_then(|selector| post.select(&selector).next());
+	let text = |elem: ElementRef| Some(elem.text().flat_map(str::split_whitespace).collect::<Vec<_>>().join(" ")).filter(|text| !text.is_empty());
+
+	let name = first(&selectors.name).and_then(text)?;
+	let avatar = selectors.avatar.as_deref().and_then(first)
+		.and_then(|img| img.value().attr("data-src").or(img.value().attr("src")))
+		// Discord only accepts absolute URLs
+		.map(|avatar| if avatar.starts_with("//") { format!("https:{avatar}") } else { avatar.to_owned() });
+	// Badges are either text or an image with a title
+	let badge = selectors.badge.as_deref().and_then(first)
+		.and_then(|badge| text(badge).or(badge.value().attr("title").map(ToOwned::to_owned)));
+	Some(Author {
+		name,
+		avatar,
+		badge,
+	})
+}
+
+/// Text of the first element matching the selector, one line per paragraph or other block
+fn article_text(html: &Html, selector: &str) -> Result<String, NewsError> {
+	let selector = format_selector(selector)?;
+	let Some(elem) = html.select(&selector).next() else {
+		return Ok(String::new());
+	};
+
+	let mut text = String::new();
+	push_text(elem, &mut text);
+	Ok(text.lines()
+		.map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+		.filter(|line| !line.is_empty())
+		.collect::<Vec<_>>()
+		.join("\n"))
+}
+
+fn push_text(elem: ElementRef, text: &mut String) {
+	for child in elem.children() {
+		if let Some(child_text) = child.value().as_text() {
+			text.push_str(child_text);
+		} else if let Some(child) = ElementRef::wrap(child) {
+			let name = child.value().name();
+			if HIDDEN_ELEMENTS.contains(&name) {
+				continue;
+			}
+			push_text(child, text);
+			if BLOCK_ELEMENTS.contains(&name) {
+				text.push('\n');
+			}
 		}
-		ScrapeType::Main => {
-			let title_elem = html.select_first("head>meta:nth-child(13)", post_url)?;
-			(
-				title_elem.select_attribute("content", post_url)?,
-				scrape_news_image(html).unwrap_or(EMPTY_IMG.to_owned()),
-				sanitize_html(&get_next_selector(html, "p", ScrapeType::Main, post_url)?)
-			)
+	}
+}
+
+/// Collects a single embed field as described by the sources definition
+fn resolve_meta(html: &Html, meta: &MetaSelector, scrape_type: ScrapeType, post_url: &str) -> Result<String, NewsError> {
+	match meta {
+		MetaSelector::Attribute { selector, attributes } => {
+			let sel = format_selector(selector)?;
+			html.select(&sel)
+				.find_map(|elem| attributes.iter().find_map(|attr| elem.value().attr(attr)))
+				.map(ToOwned::to_owned)
+				.ok_or(NewsError::SelectedNothing(selector.clone(), post_url.to_owned()))
 		}
-		ScrapeType::Changelog => {
-			let title_elem = html.select_first("head>meta:nth-child(13)", post_url)?;
-			(
-				title_elem.select_attribute("content", post_url)?,
-				scrape_news_image(html).unwrap_or(EMPTY_IMG.to_owned()),
-				"The current provided changelog reflects the major changes within the game as part of this Update. Some updates, additions and fixes may not be listed in the provided notes. War Thunder is constantly improving and specific fixes may be implemented without the client being updated.".to_owned()
-			)
+		MetaSelector::Text { selector, min_length } => {
+			Ok(sanitize_html(&get_next_selector(html, selector, *min_length, scrape_type, post_url)?))
 		}
-	};
-
-	Ok(EmbedData::new(&title, post_url, &img_url, &preview_text, scrape_type))
+		MetaSelector::ArticleText { selector } => {
+			let text: String = article_text(html, selector)?.chars().take(PREVIEW_LENGTH).collect();
+			if text.is_empty() {
+				return Err(NewsError::SelectedNothing(selector.clone(), post_url.to_owned()));
+			}
+			Ok(text)
+		}
+		MetaSelector::Fixed { text } => {
+			Ok(text.clone())
+		}
+		MetaSelector::Meta { names } => {
+			let sel = format_selector("meta")?;
+			// The order of the names decides, not the order of the tags in the document
+			names.iter()
+				.find_map(|name| {
+					html.select(&sel)
+						.filter(|elem| ["property", "name"].iter().any(|attr| elem.value().attr(attr).map_or(false, |value| value.eq_ignore_ascii_case(name))))
+						.find_map(|elem| elem.value().attr("content").map(str::trim).filter(|content| !content.is_empty()))
+				})
+				.map(ToOwned::to_owned)
+				.ok_or(NewsError::SelectedNothing(format!("meta {}", names.join(", ")), post_url.to_owned()))
+		}
+		MetaSelector::Chain { chain } => {
+			let mut last_err = NewsError::MetaCannotBeScraped(scrape_type, post_url.to_owned());
+			for meta in chain {
+				match resolve_meta(html, meta, scrape_type, post_url) {
+					Ok(found) => {
+						return Ok(found);
+					}
+					Err(e) => {
+						last_err = e;
+					}
+				}
+			}
+			Err(last_err)
+		}
+	}
 }
 
 /// Returns sufficiently long string as description for embed
-fn get_next_selector(html: &Html, selector: &str, scrape_type: ScrapeType, post_url: &str) -> Result<String, NewsError> {
+fn get_next_selector(html: &Html, selector: &str, min_length: usize, scrape_type: ScrapeType, post_url: &str) -> Result<String, NewsError> {
 	let selector = format_selector(selector)?;
 	let selected = html.select(&selector);
 	for item in selected {
-		if item.inner_html().len() >= 10 {
+		if item.inner_html().len() >= min_length {
 			return Ok(item.inner_html());
 		}
 	}
@@ -51,7 +152,7 @@ fn get_next_selector(html: &Html, selector: &str, scrape_type: ScrapeType, post_
 }
 
 // Builds discord ready embed URL from html anchors
-fn sanitize_html(html: &str) -> String {
+pub(crate) fn sanitize_html(html: &str) -> String {
 	static SPECIAL_DELIM: char = '🦆'; // Quack quack :D
 
 	let urls = {
@@ -114,42 +215,29 @@ fn sanitize_html(html: &str) -> String {
 	constructed
 }
 
-/// Collects meta image to display for embed
-fn scrape_news_image(html: &Html) -> Result<String, NewsError> {
-	let mut actual = String::new();
-	for item in html.select(&format_selector("meta, img")?) {
-		if let Some(proper_image) = item.value().attr("content") {
-			if proper_image.contains("https://warthunder.com/upload/image//!") && item.value().attr("name") != Some("twitter:image") {
-				actual = proper_image.to_owned();
-				break;
-			}
-		}
-
-		if let Some(proper_image) = item.value().attr("src") {
-			// Tracking pixels which are styled to be invisible should be ignored!
-			if item.value().attrs().find(|e|e.0 == "style" && e.1.contains("display: none")).is_some() {
-				continue;
-			}
-			actual = proper_image.to_owned();
-			break;
-		}
-	}
-	println!("{}", actual);
-	Ok(actual)
-}
-
 #[cfg(test)]
 mod tests {
-	use crate::scrapers::scrape_meta::{sanitize_html, scrape_meta};
+	use scraper::Html;
+
+	use crate::embed::Author;
+	use crate::json::selectors::Selectors;
+	use crate::json::sources::Sources;
+	use crate::scrapers::news_source::SourceRegistry;
+	use crate::scrapers::scrape_meta::{article_text, resolve_meta, sanitize_html, scrape_meta};
 	use crate::scrapers::scraper_resources::resources::{request_html, ScrapeType};
 
+	fn selectors(name: &str) -> Selectors {
+		let sources = Sources::new(&SourceRegistry::default()).unwrap();
+		sources.sources.into_iter().find(|source| source.name == name).unwrap().selectors.unwrap()
+	}
+
 	#[tokio::test]
 	async fn test_embed_data_main() {
 		// let url = "https://warthunder.com/en/news/7598-development-lav-ad-revolving-firepower-en";
 		let url = "https://warthunder.com/en/news/7640-event-the-battle-for-arachis-en";
 		let html = request_html(url).await.unwrap();
 
-		eprintln!("{:#?}", scrape_meta(&html, ScrapeType::Main, &url.to_owned()));
+		eprintln!("{:#?}", scrape_meta(&html, &selectors("warthunder_news"), ScrapeType::Main, url));
 	}
 
 	#[tokio::test]
@@ -157,7 +245,7 @@ mod tests {
 		let url = "https://warthunder.com/en/game/changelog/current/1352";
 		let html = request_html(url).await.unwrap();
 
-		eprintln!("{:#?}", scrape_meta(&html, ScrapeType::Changelog, &url.to_owned()));
+		eprintln!("{:#?}", scrape_meta(&html, &selectors("warthunder_changelog"), ScrapeType::Changelog, url));
 	}
 
 	#[tokio::test]
@@ -165,7 +253,7 @@ mod tests {
 		let url = "https://warthunder.com/en/news/8199-it-s-fixed-73-en";
 		let html = request_html(url).await.unwrap();
 
-		eprintln!("{:#?}", scrape_meta(&html, ScrapeType::Main, &url.to_owned()));
+		eprintln!("{:#?}", scrape_meta(&html, &selectors("warthunder_news"), ScrapeType::Main, url));
 	}
 
 	#[test]
@@ -175,4 +263,72 @@ mod tests {
 
 		assert_eq!(sanitize_html(RAW), ESCAPED);
 	}
+
+	#[test]
+	fn test_image_skips_tracking_pixel() {
+		static RAW: &str = r#"<html><body><img src="https://pixel.example" style="display: none"><img src="https://warthunder.com/upload/image/title.jpg"></body></html>"#;
+		let html = Html::parse_document(RAW);
+		let image = resolve_meta(&html, &selectors("warthunder_news").image, ScrapeType::Main, "").unwrap();
+		assert_eq!(image, "https://warthunder.com/upload/image/title.jpg");
+	}
+
+	#[test]
+	fn test_semantic_meta() {
+		let title = &selectors("warthunder_news").title;
+		let resolve = |raw: &str| resolve_meta(&Html::parse_document(raw), title, ScrapeType::Main, "").ok();
+
+		// Open Graph wins over Twitter wherever either is placed in the head
+		assert_eq!(resolve(r#"<html><head><title>Site</title><meta name="twitter:title" content="Twitter"><meta charset="utf-8"><meta property="og:title" content="Open Graph"></head></html>"#), Some("Open Graph".to_owned()));
+		assert_eq!(resolve(r#"<html><head><meta property="og:title" content=" "><meta name="twitter:title" content="Twitter"></head></html>"#), Some("Twitter".to_owned()));
+		assert_eq!(resolve(r#"<html><head><title>Site</title></head></html>"#), Some("Site".to_owned()));
+		assert_eq!(resolve(r#"<html><head></head></html>"#), None);
+	}
+
+	#[test]
+	fn test_forum_post() {
+		static RAW: &str = r#"<html><head><meta property="og:title" content="Update 2.21"></head><body>
+			<article>
+				<aside>
+					<h3 class="cAuthorPane_author"><strong><a href="/profile/1">Stona_WT</a></strong></h3>
+					<ul><li class="cAuthorPane_photo"><img src="//forum.warthunder.com/avatar.png"></li><li data-role="group">Developer</li></ul>
+				</aside>
+				<div data-role="commentContent"><p><img class="ipsEmoji" src="https://forum.warthunder.com/emoticons/smile.png"></p><p>The first post of the topic.</p><img data-src="https://forum.warthunder.com/post.jpg" src="lazy.gif"></div>
+			</article>
+			<article>
+				<h3 class="cAuthorPane_author">Reply</h3>
+				<div data-role="commentContent"><p>A reply with its own picture.</p><img src="https://forum.warthunder.com/reply.jpg"></div>
+			</article>
+		</body></html>"#;
+		let html = Html::parse_document(RAW);
+		let embed = scrape_meta(&html, &selectors("forums_updates_information"), ScrapeType::Forum, "").unwrap();
+
+		assert_eq!(embed.preview_text, "The first post of the topic.");
+		assert_eq!(embed.img_url, "https://forum.warthunder.com/post.jpg");
+		assert_eq!(embed.author, Some(Author {
+			name: "Stona_WT".to_owned(),
+			avatar: Some("https://forum.warthunder.com/avatar.png".to_owned()),
+			badge: Some("Developer".to_owned()),
+		}));
+	}
+
+	#[test]
+	fn test_forum_post_without_text() {
+		static RAW: &str = r#"<html><head><meta property="og:title" content="New camouflages"><meta property="og:description" content="Have a look at the new camouflages"></head><body>
+			<article><div data-role="commentContent"><p><img src="https://forum.warthunder.com/camouflages.jpg"></p></div></article>
+		</body></html>"#;
+		let html = Html::parse_document(RAW);
+		let embed = scrape_meta(&html, &selectors("forums_updates_information"), ScrapeType::Forum, "").unwrap();
+
+		// A first post holding only an image falls back to the description of the page
+		assert_eq!(embed.preview_text, "Have a look at the new camouflages");
+		assert_eq!(embed.img_url, "https://forum.warthunder.com/camouflages.jpg");
+	}
+
+	#[test]
+	fn test_article_text() {
+		static RAW: &str = r#"<html><body><div class="content__body"><h2>Changes</h2><p>Fixed a  bug with <b>tracks</b>.</p><script>track()</script><ul><li>One</li><li>Two</li></ul></div></body></html>"#;
+		let html = Html::parse_document(RAW);
+		assert_eq!(article_text(&html, "div.content__body").unwrap(), "Changes\nFixed a bug with tracks.\nOne\nTwo");
+		assert_eq!(article_text(&html, "article").unwrap(), "");
+	}
 }
\ No newline at end of file
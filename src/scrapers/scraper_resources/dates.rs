@@ -0,0 +1,33 @@
+use chrono::{DateTime, NaiveDate};
+
+// Formats of plain dates found on pages, tried in order
+const DATE_FORMATS: [&str; 5] = ["%d %B %Y", "%B %d, %Y", "%Y-%m-%d", "%d.%m.%Y", "%d/%m/%Y"];
+
+/// Reads the dates pages commonly state, such as "2022-10-16T12:00:00+00:00" or "16 October 2022", as UTC timestamp
+pub fn parse_date(text: &str) -> Option<i64> {
+	let text = text.trim();
+	if let Ok(date) = DateTime::parse_from_rfc3339(text) {
+		return Some(date.timestamp());
+	}
+	if let Ok(date) = DateTime::parse_from_rfc2822(text) {
+		return Some(date.timestamp());
+	}
+	DATE_FORMATS.iter()
+		.find_map(|format| NaiveDate::parse_from_str(text, format).ok())
+		.and_then(|date| date.and_hms_opt(0, 0, 0))
+		.map(|date| date.and_utc().timestamp())
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::scrapers::scraper_resources::dates::parse_date;
+
+	#[test]
+	fn test_parse_date() {
+		assert_eq!(parse_date("2022-10-16T12:00:00+02:00"), Some(1_665_914_400));
+		assert_eq!(parse_date("Sun, 16 Oct 2022 10:00:00 GMT"), Some(1_665_914_400));
+		assert_eq!(parse_date(" 16 October 2022 "), Some(1_665_878_400));
+		assert_eq!(parse_date("16.10.2022"), Some(1_665_878_400));
+		assert_eq!(parse_date("yesterday"), None);
+	}
+}
\ No newline at end of file
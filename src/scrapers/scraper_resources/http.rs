@@ -0,0 +1,93 @@
+use std::time::Duration;
+
+use lazy_static::lazy_static;
+use reqwest::{Client, Proxy, Response, StatusCode};
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use tokio::sync::OwnedSemaphorePermit;
+use tracing::warn;
+
+use crate::CONFIG;
+use crate::error::NewsError;
+use crate::scrapers::scraper_resources::politeness;
+
+lazy_static! {
+	/// Shared by every request so connections are pooled
+	pub static ref HTTP_CLIENT: Client = {
+		let http = &CONFIG.http;
+		let mut builder = Client::builder()
+			.user_agent(&http.user_agent)
+			.timeout(Duration::from_secs(http.timeout))
+			.connect_timeout(Duration::from_secs(http.connect_timeout));
+		if let Some(proxy) = &http.proxy {
+			builder = builder.proxy(Proxy::all(proxy).expect("The configured proxy is not a valid URL"));
+		}
+		builder.build().expect("Cannot build the HTTP client")
+	};
+}
+
+/// Sends a GET request within the politeness limits of its domain, retrying transient failures with exponential backoff.
+/// The returned permit has to be held until the body was read, waits between attempts hold none
+pub async fn get(url: &str, headers: HeaderMap) -> Result<(Response, Option<OwnedSemaphorePermit>), NewsError> {
+	let http = &CONFIG.http;
+	let mut attempt = 0;
+	loop {
+		let permit = politeness::acquire(url).await;
+		let result = HTTP_CLIENT.get(url).headers(headers.clone()).send().await;
+
+		// The server may ask for a specific wait, which replaces the backoff
+		let retry_after = match &result {
+			Ok(response) if is_transient(response.status()) => retry_after(response),
+			Err(e) if e.is_timeout() || e.is_connect() => None,
+			_ => return Ok((result?, permit)),
+		};
+		if attempt >= http.max_retries {
+			return Ok((result?, permit));
+		}
+		drop(permit);
+
+		let backoff = retry_after
+			.unwrap_or_else(|| Duration::from_millis(http.backoff.saturating_mul(1 << attempt.min(16))))
+			.min(Duration::from_secs(http.max_backoff));
+		match &result {
+			Ok(response) => warn!("{url} returned {}, retrying in {backoff:?}", response.status()),
+			Err(e) => warn!("Fetching {url} failed with \"{e}\", retrying in {backoff:?}"),
+		}
+		tokio::time::sleep(backoff).await;
+		attempt += 1;
+	}
+}
+
+fn is_transient(status: StatusCode) -> bool {
+	matches!(status, StatusCode::TOO_MANY_REQUESTS | StatusCode::INTERNAL_SERVER_ERROR | StatusCode::BAD_GATEWAY | StatusCode::SERVICE_UNAVAILABLE | StatusCode::GATEWAY_TIMEOUT)
+}
+
+/// Reads Retry-After, which is either a number of seconds or an HTTP-date
+fn retry_after(response: &Response) -> Option<Duration> {
+	let value = response.headers().get(RETRY_AFTER)?.to_str().ok()?;
+	parse_retry_after(value, chrono::Utc::now().timestamp())
+}
+
+fn parse_retry_after(value: &str, now: i64) -> Option<Duration> {
+	let value = value.trim();
+	if let Ok(seconds) = value.parse::<u64>() {
+		return Some(Duration::from_secs(seconds));
+	}
+	let date = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+	Some(Duration::from_secs(u64::try_from(date.timestamp() - now).unwrap_or(0)))
+}
+
+#[cfg(test)]
+mod tests {
+	use std::time::Duration;
+
+	use crate::scrapers::scraper_resources::http::parse_retry_after;
+
+	#[test]
+	fn test_retry_after() {
+		assert_eq!(parse_retry_after("120", 0), Some(Duration::from_secs(120)));
+		assert_eq!(parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT", 1_445_412_450), Some(Duration::from_secs(30)));
+		// Dates in the past allow retrying right away
+		assert_eq!(parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT", 1_445_412_500), Some(Duration::ZERO));
+		assert_eq!(parse_retry_after("soon", 0), None);
+	}
+}
\ No newline at end of file
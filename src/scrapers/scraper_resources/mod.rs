@@ -1,2 +1,6 @@
 pub mod resources;
-pub mod html_util;
\ No newline at end of file
+pub mod html_util;
+pub mod politeness;
+pub mod robots;
+pub mod http;
+pub mod dates;
\ No newline at end of file
@@ -0,0 +1,87 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use lazy_static::lazy_static;
+use reqwest::Url;
+use tokio::sync::{Mutex, OwnedSemaphorePermit, Semaphore};
+use tokio::time::Instant;
+
+// Requests running against the same domain at the same time
+const DOMAIN_CONCURRENCY: usize = 2;
+
+// Minimum time between the start of two requests against the same domain
+const DOMAIN_REQUEST_GAP: Duration = Duration::from_secs(1);
+
+lazy_static! {
+	static ref DOMAINS: std::sync::Mutex<HashMap<String, Arc<DomainLimit>>> = std::sync::Mutex::new(HashMap::new());
+}
+
+#[derive(Debug)]
+/// Caps how hard a single domain is hit, regardless of how many sources live on it
+struct DomainLimit {
+	permits: Arc<Semaphore>,
+	/// When the next request may start, and the gap enforced between requests
+	next_start: Mutex<(Instant, Duration)>,
+}
+
+impl DomainLimit {
+	fn new() -> Self {
+		Self {
+			permits: Arc::new(Semaphore::new(DOMAIN_CONCURRENCY)),
+			next_start: Mutex::new((Instant::now(), DOMAIN_REQUEST_GAP)),
+		}
+	}
+}
+
+fn domain_limit(host: String) -> Arc<DomainLimit> {
+	Arc::clone(DOMAINS.lock().unwrap().entry(host).or_insert_with(|| Arc::new(DomainLimit::new())))
+}
+
+/// Waits until a request against the domain of the URL is allowed, the permit has to be held until the request is done
+pub async fn acquire(url: &str) -> Option<OwnedSemaphorePermit> {
+	let host = Url::parse(url).ok()?.host_str()?.to_owned();
+	let limit = domain_limit(host);
+
+	let permit = Arc::clone(&limit.permits).acquire_owned().await.ok()?;
+	let mut next_start = limit.next_start.lock().await;
+	tokio::time::sleep_until(next_start.0).await;
+	next_start.0 = Instant::now() + next_start.1;
+	Some(permit)
+}
+
+/// Widens the gap between requests against a domain, such as for a robots.txt Crawl-delay
+pub async fn set_request_gap(host: &str, gap: Duration) {
+	let limit = domain_limit(host.to_owned());
+	let mut next_start = limit.next_start.lock().await;
+	next_start.1 = gap.max(DOMAIN_REQUEST_GAP);
+}
+#[cfg(test)]
+mod tests {
+	use std::time::Duration;
+
+	use tokio::time::Instant;
+
+	use crate::scrapers::scraper_resources::politeness::acquire;
+
+	#[tokio::test(start_paused = true)]
+	async fn test_domain_limit() {
+		let start = Instant::now();
+		let first = acquire("https://limited.example/1").await.unwrap();
+		let _second = acquire("https://limited.example/2").await.unwrap();
+		// Requests against one domain start a second apart
+		assert_eq!(start.elapsed(), Duration::from_secs(1));
+
+		// Other domains are not held up by it
+		let _other = acquire("https://other.example/1").await.unwrap();
+		assert_eq!(start.elapsed(), Duration::from_secs(1));
+
+		// A third concurrent request waits until one of the first two is done
+		let third = tokio::spawn(async { acquire("https://limited.example/3").await.is_some() });
+		tokio::time::sleep(Duration::from_secs(5)).await;
+		assert!(!third.is_finished());
+		drop(first);
+		assert!(third.await.unwrap());
+		assert_eq!(start.elapsed(), Duration::from_secs(6));
+	}
+}
@@ -1,12 +1,30 @@
+use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
-use std::time::Duration;
 
-use reqwest::Client;
+use lazy_static::lazy_static;
+use reqwest::StatusCode;
+use reqwest::header::{ETAG, HeaderMap, HeaderValue, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
 use scraper::Html;
 use tracing::info;
 
 use crate::error::NewsError;
-use crate::scrapers::scraper_resources::html_util::{ElemUtil, format_selector, HtmlUtil};
+use crate::json::selectors::Selectors;
+use crate::scrapers::scraper_resources::html_util::format_selector;
+use crate::scrapers::scraper_resources::{http, robots};
+
+lazy_static! {
+	/// Validators of the documents whose listing was parsed and cached, the only ones sent back to the server
+	static ref VALIDATORS: std::sync::Mutex<HashMap<String, Validators>> = std::sync::Mutex::new(HashMap::new());
+	/// Validators of the last response per URL, until its listing was parsed and cached
+	static ref PENDING_VALIDATORS: std::sync::Mutex<HashMap<String, Validators>> = std::sync::Mutex::new(HashMap::new());
+}
+
+#[derive(Clone, Debug, Default)]
+/// Cache validators a server returned for a URL, sent back to only receive changed documents
+struct Validators {
+	etag: Option<String>,
+	last_modified: Option<String>,
+}
 
 #[derive(serde::Serialize, serde::Deserialize, PartialEq, Eq, Clone, Copy, Debug)]
 /// Defines the types of pages where news come from
@@ -17,16 +35,12 @@ pub enum ScrapeType {
 }
 
 impl ScrapeType {
-	// Used for API calls or similar
-	pub fn infer_from_url(url: &str) -> Self {
-		if url.contains("forum.warthunder.com") {
-			Self::Forum
-		} else {
-			if url.contains("changelog") {
-				Self::Changelog
-			} else {
-				Self::Main
-			}
+	/// Name as used in the source definitions
+	pub const fn name(self) -> &'static str {
+		match self {
+			Self::Forum => "Forum",
+			Self::Main => "Main",
+			Self::Changelog => "Changelog",
 		}
 	}
 }
@@ -48,66 +62,120 @@ impl Display for ScrapeType {
 }
 
 pub async fn request_html(url: &str) -> Result<Html, NewsError> {
+	let text = request_text(url).await?;
+	Ok(Html::parse_document(text.as_str()))
+}
+
+/// Fetches the raw body of any document, such as feeds or JSON. Documents which were taken down return `NewsError::Gone`
+pub async fn request_text(url: &str) -> Result<String, NewsError> {
+	robots::check_allowed(url).await?;
 	info!("Fetching data from {}", &url);
 
-	let client = Client::builder()
-		.timeout(Duration::from_secs(5))
-		.build()?;
-	let raw_html = client.get(url).send().await?;
-	let text = raw_html.text().await?;
-	Ok(Html::parse_document(text.as_str()))
+	let (response, _permit) = http::get(url, HeaderMap::new()).await?;
+	if is_gone_status(response.status()) {
+		return Err(NewsError::Gone(url.to_owned()));
+	}
+	Ok(response.text().await?)
 }
 
-pub fn get_listed_links(scrape_type: ScrapeType, html: &Html) -> Result<Vec<String>, NewsError> {
-	return match scrape_type {
-		ScrapeType::Changelog | ScrapeType::Main => {
-			let sel_text = if scrape_type == ScrapeType::Main {
-				// ---------------------------------------------------------↓ I dont make the rules ¯\_(ツ)_/¯
-				"div.showcase__item"
-			} else {
-				// ---------------------------------------------------------↓ I dont make the rules ¯\_(ツ)_/¯
-				"div.showcase__item"
-			};
-			let sel = format_selector(sel_text)?;
-
-			let selected = html.select(&sel);
-			let mut res = vec![];
-			for item in selected {
-				if let Ok(url) = item.select_first("a", &scrape_type.to_string())?.select_attribute("href", &scrape_type.to_string()) {
-					res.push(url.clone());
-				} else {
-				}
-			}
-			Ok(res)
-		}
-		ScrapeType::Forum => {
-			static SEL_TEXT: &str = "body > main > div > div > div > div:nth-child(2) > div > ol > li";
-			let sel = format_selector(SEL_TEXT)?;
-
-			let lower_url_test = "div > h4 > div > a";
-			let lower_url = format_selector(lower_url_test)?;
-
-			let selected = html.select(&sel);
-			let mut res = vec![];
-			for item in selected {
-				if let Some(url_elem) = item.select(&lower_url).next() {
-					if let Some(url) = url_elem.value().attr("href") {
-						res.push(url.to_owned());
-					}
-				}
+const fn is_gone_status(status: StatusCode) -> bool {
+	matches!(status, StatusCode::NOT_FOUND | StatusCode::GONE)
+}
+
+/// Fetches a document unless the server reports that it did not change since the last fetch, which returns None.
+/// The validators of the response are only sent back once [`keep_validators`] confirms that the document was cached
+pub async fn request_text_if_modified(url: &str) -> Result<Option<String>, NewsError> {
+	robots::check_allowed(url).await?;
+	info!("Fetching data from {}", &url);
+
+	let (response, _permit) = http::get(url, conditional_headers(url)).await?;
+	if response.status() == StatusCode::NOT_MODIFIED {
+		info!("{url} did not change since it was last fetched");
+		return Ok(None);
+	}
+	if is_gone_status(response.status()) {
+		return Err(NewsError::Gone(url.to_owned()));
+	}
+
+	if response.status().is_success() {
+		let header = |name| response.headers().get(name).and_then(|value: &HeaderValue| value.to_str().ok()).map(ToOwned::to_owned);
+		let validators = Validators {
+			etag: header(ETAG),
+			last_modified: header(LAST_MODIFIED),
+		};
+		PENDING_VALIDATORS.lock().unwrap().insert(url.to_owned(), validators);
+	}
+	Ok(Some(response.text().await?))
+}
+
+/// Sends the validators of the last response for the URL with further requests, as its document is cached now
+pub fn keep_validators(url: &str) {
+	if let Some(validators) = PENDING_VALIDATORS.lock().unwrap().remove(url) {
+		VALIDATORS.lock().unwrap().insert(url.to_owned(), validators);
+	}
+}
+
+/// Requests the URL in full again, such as when there is no cached document a 304 could refer to
+pub fn forget_validators(url: &str) {
+	VALIDATORS.lock().unwrap().remove(url);
+}
+
+fn conditional_headers(url: &str) -> HeaderMap {
+	let mut headers = HeaderMap::new();
+	if let Some(validators) = VALIDATORS.lock().unwrap().get(url) {
+		let values = [(IF_NONE_MATCH, &validators.etag), (IF_MODIFIED_SINCE, &validators.last_modified)];
+		for (name, value) in values {
+			if let Some(value) = value.as_deref().and_then(|value| HeaderValue::from_str(value).ok()) {
+				headers.insert(name, value);
 			}
-			Ok(res)
 		}
-	};
+	}
+	headers
 }
 
-pub fn format_into_final_url(top_url: &str, selection: ScrapeType) -> String {
-	match selection {
-		ScrapeType::Main | ScrapeType::Changelog => {
-			format!("https://warthunder.com{}", top_url)
-		}
-		ScrapeType::Forum => {
-			top_url.to_owned()
+/// Collects all links listed on a sources page as described by its selectors
+pub fn get_listed_links(selectors: &Selectors, domain: &str, html: &Html) -> Result<Vec<String>, NewsError> {
+	let listing = format_selector(&selectors.listing)?;
+	let link = format_selector(&selectors.link)?;
+
+	let mut res = vec![];
+	for item in html.select(&listing) {
+		if let Some(url) = item.select(&link).next().and_then(|elem| elem.value().attr(&selectors.link_attribute)) {
+			res.push(url.to_owned());
 		}
 	}
-}
\ No newline at end of file
+
+	// An empty listing is most likely a changed layout rather than a lack of news
+	if res.is_empty() {
+		return Err(NewsError::SelectedNothing(selectors.listing.clone(), domain.to_owned()));
+	}
+	Ok(res)
+}
+
+#[cfg(test)]
+mod tests {
+	use reqwest::header::{IF_MODIFIED_SINCE, IF_NONE_MATCH};
+
+	use crate::scrapers::scraper_resources::resources::{conditional_headers, forget_validators, keep_validators, PENDING_VALIDATORS, Validators};
+
+	#[test]
+	fn test_validators_wait_for_cached_listing() {
+		let url = "https://example.com/validators";
+		let validators = Validators {
+			etag: Some("\"abc\"".to_owned()),
+			last_modified: None,
+		};
+		PENDING_VALIDATORS.lock().unwrap().insert(url.to_owned(), validators);
+
+		// The listing of the response was not cached yet
+		assert!(conditional_headers(url).is_empty());
+
+		keep_validators(url);
+		let headers = conditional_headers(url);
+		assert_eq!(headers.get(IF_NONE_MATCH).unwrap(), "\"abc\"");
+		assert!(headers.get(IF_MODIFIED_SINCE).is_none());
+
+		forget_validators(url);
+		assert!(conditional_headers(url).is_empty());
+	}
+}
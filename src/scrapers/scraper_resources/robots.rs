@@ -0,0 +1,273 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use lazy_static::lazy_static;
+use reqwest::{StatusCode, Url};
+use reqwest::header::HeaderMap;
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+use tracing::warn;
+
+use crate::CONFIG;
+use crate::error::NewsError;
+use crate::scrapers::scraper_resources::{http, politeness};
+
+// robots.txt is fetched again once it is older than this
+const ROBOTS_TTL: Duration = Duration::from_secs(60 * 60 * 24);
+
+lazy_static! {
+	static ref ROBOTS_CACHE: Mutex<HashMap<String, (Instant, Arc<Robots>)>> = Mutex::new(HashMap::new());
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+/// Rules of a robots.txt that apply to this handler
+pub struct Robots {
+	rules: Vec<Rule>,
+	pub crawl_delay: Option<Duration>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Rule {
+	allow: bool,
+	pattern: String,
+}
+
+#[derive(Debug, Default)]
+struct Group {
+	agents: Vec<String>,
+	rules: Vec<Rule>,
+	crawl_delay: Option<Duration>,
+}
+
+impl Robots {
+	/// Collects the groups naming the product token of the agent, or the groups for "*" if none does
+	pub fn parse(text: &str, agent: &str) -> Self {
+		let mut groups: Vec<Group> = vec![];
+		let mut in_rules = true;
+
+		for line in text.lines() {
+			let line = line.split('#').next().unwrap_or_default().trim();
+			let Some((key, value)) = line.split_once(':') else {
+				continue;
+			};
+			let value = value.trim();
+
+			match key.trim().to_lowercase().as_str() {
+				"user-agent" => {
+					// Consecutive User-agent lines share one group
+					if in_rules {
+						groups.push(Group::default());
+						in_rules = false;
+					}
+					// Only the product token is compared, such as "examplebot" of "ExampleBot/1.0"
+					let token = value.split(['/', ' ']).next().unwrap_or_default().to_lowercase();
+					if let (Some(group), false) = (groups.last_mut(), token.is_empty()) {
+						group.agents.push(token);
+					}
+				}
+				key @ ("allow" | "disallow") => {
+					in_rules = true;
+					// An empty Disallow allows everything, which is the default anyway
+					if let (Some(group), false) = (groups.last_mut(), value.is_empty()) {
+						group.rules.push(Rule {
+							allow: key == "allow",
+							pattern: value.to_owned(),
+						});
+					}
+				}
+				"crawl-delay" => {
+					in_rules = true;
+					if let (Some(group), Ok(seconds)) = (groups.last_mut(), value.parse::<f64>()) {
+						if seconds.is_finite() && seconds >= 0.0 {
+							group.crawl_delay = Some(Duration::from_secs_f64(seconds));
+						}
+					}
+				}
+				_ => {}
+			}
+		}
+
+		let agent = agent.split(['/', ' ']).next().unwrap_or_default().to_lowercase();
+		let names_agent = |group: &Group| !agent.is_empty() && group.agents.contains(&agent);
+		let named = groups.iter().any(names_agent);
+		let mut robots = Self::default();
+		for group in groups {
+			let applies = if named {
+				names_agent(&group)
+			} else {
+				group.agents.iter().any(|name| name == "*")
+			};
+			if applies {
+				robots.rules.extend(group.rules);
+				robots.crawl_delay = robots.crawl_delay.max(group.crawl_delay);
+			}
+		}
+		robots
+	}
+
+	/// The longest matching rule decides, allow wins a tie
+	pub fn is_allowed(&self, path: &str) -> bool {
+		self.rules.iter()
+			.filter(|rule| pattern_matches(&rule.pattern, path))
+			.max_by_key(|rule| (rule.pattern.len(), rule.allow))
+			.map_or(true, |rule| rule.allow)
+	}
+}
+
+/// Matches robots.txt patterns, which support the * wildcard and the $ end anchor
+fn pattern_matches(pattern: &str, path: &str) -> bool {
+	let (pattern, anchored) = match pattern.strip_suffix('$') {
+		Some(pattern) => (pattern, true),
+		None => (pattern, false),
+	};
+
+	let mut parts = pattern.split('*');
+	let first = parts.next().unwrap_or_default();
+	if !path.starts_with(first) {
+		return false;
+	}
+
+	let rest: Vec<&str> = parts.collect();
+	let mut position = first.len();
+	for (i, part) in rest.iter().enumerate() {
+		if anchored && i == rest.len() - 1 {
+			return path[position..].ends_with(part);
+		}
+		match path[position..].find(part) {
+			Some(found) => position += found + part.len(),
+			None => return false,
+		}
+	}
+	!anchored || position == path.len()
+}
+
+/// Returns the robots.txt rules for the origin of the URL, fetching them when not cached
+pub async fn robots_for(url: &str) -> Result<Arc<Robots>, NewsError> {
+	let parsed = Url::parse(url).map_err(|_| NewsError::RobotsDisallowed(url.to_owned()))?;
+	let origin = parsed.origin().ascii_serialization();
+
+	if let Some((fetched, robots)) = ROBOTS_CACHE.lock().await.get(&origin) {
+		if fetched.elapsed() < ROBOTS_TTL {
+			return Ok(Arc::clone(robots));
+		}
+	}
+
+	let robots_url = format!("{origin}/robots.txt");
+	let (response, _permit) = http::get(&robots_url, HeaderMap::new()).await?;
+	// Matched against User-agent lines, groups for "*" apply when no group names it
+	let agent = CONFIG.http.product_token();
+	let robots = match response.status() {
+		status if status.is_success() => Robots::parse(&response.text().await?, agent),
+		// A missing robots.txt places no restrictions
+		StatusCode::NOT_FOUND | StatusCode::GONE | StatusCode::FORBIDDEN | StatusCode::UNAUTHORIZED => Robots::default(),
+		_ => {
+			return Err(response.error_for_status().err().map_or(NewsError::RobotsDisallowed(robots_url), NewsError::from));
+		}
+	};
+
+	if let Some(crawl_delay) = robots.crawl_delay {
+		if let Some(host) = parsed.host_str() {
+			politeness::set_request_gap(host, crawl_delay).await;
+		}
+	}
+
+	let robots = Arc::new(robots);
+	ROBOTS_CACHE.lock().await.insert(origin, (Instant::now(), Arc::clone(&robots)));
+	Ok(robots)
+}
+
+#[cfg(test)]
+/// Caches the robots.txt of the origin of the URL, so that it is not fetched
+pub(crate) async fn cache_robots(url: &str, text: &str) {
+	let origin = Url::parse(url).unwrap().origin().ascii_serialization();
+	let robots = Robots::parse(text, CONFIG.http.product_token());
+	ROBOTS_CACHE.lock().await.insert(origin, (Instant::now(), Arc::new(robots)));
+}
+
+/// Fails when the URL may not be fetched according to its robots.txt
+pub async fn check_allowed(url: &str) -> Result<(), NewsError> {
+	let robots = robots_for(url).await?;
+	let parsed = Url::parse(url).map_err(|_| NewsError::RobotsDisallowed(url.to_owned()))?;
+
+	let mut path = parsed.path().to_owned();
+	if let Some(query) = parsed.query() {
+		path.push('?');
+		path.push_str(query);
+	}
+
+	if robots.is_allowed(&path) {
+		Ok(())
+	} else {
+		warn!("robots.txt disallows fetching {url}");
+		Err(NewsError::RobotsDisallowed(url.to_owned()))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::time::Duration;
+
+	use crate::scrapers::scraper_resources::robots::Robots;
+
+	const AGENT: &str = "wt_event_handler";
+
+	static ROBOTS: &str = "
+# Generic crawlers
+User-agent: *
+Disallow: /admin/
+Disallow: /*.php$
+Allow: /admin/public/
+
+User-agent: wt_event_handler
+User-agent: SomeOtherBot
+Disallow: /en/news/private
+Crawl-delay: 5
+";
+
+	#[test]
+	fn test_named_group_wins() {
+		let robots = Robots::parse(ROBOTS, AGENT);
+		assert_eq!(robots.crawl_delay, Some(Duration::from_secs(5)));
+		assert!(!robots.is_allowed("/en/news/private-post"));
+		// Rules of the wildcard group do not apply once a group names the agent
+		assert!(robots.is_allowed("/admin/secret"));
+	}
+
+	#[test]
+	fn test_only_the_product_token_names_the_agent() {
+		let robots = "
+User-agent: *
+Disallow: /private/
+
+User-agent:
+Disallow: /
+
+User-agent: wt_event
+Disallow: /
+
+User-agent: WT_Event_Handler/2.0
+Disallow: /drafts/
+";
+		let robots = Robots::parse(robots, "wt_event_handler/1.0 (+https://example.com)");
+		assert!(!robots.is_allowed("/drafts/post"));
+		// Neither the empty agent nor a part of the token name this handler
+		assert!(robots.is_allowed("/en/news"));
+		assert!(robots.is_allowed("/private/page"));
+
+		let robots = Robots::parse("User-agent:\nDisallow: /\n\nUser-agent: *\nDisallow: /private/\n", AGENT);
+		assert!(robots.is_allowed("/en/news"));
+		assert!(!robots.is_allowed("/private/page"));
+	}
+
+	#[test]
+	fn test_wildcard_group() {
+		let robots = Robots::parse(ROBOTS, "UnknownBot");
+		assert_eq!(robots.crawl_delay, None);
+		assert!(!robots.is_allowed("/admin/secret"));
+		assert!(robots.is_allowed("/admin/public/page"));
+		assert!(!robots.is_allowed("/index.php"));
+		assert!(robots.is_allowed("/index.php?/forum/126-updates-information-read-only/"));
+		assert!(robots.is_allowed("/en/news"));
+	}
+}
\ No newline at end of file
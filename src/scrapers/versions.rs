@@ -0,0 +1,85 @@
+use std::cmp::Ordering;
+
+use tracing::{error, warn};
+
+use crate::api::database::Database;
+use crate::api::database_queries::GameVersion;
+use crate::embed::EmbedData;
+
+/// Adds the version a changelog announces to the history, and tells opted-in webhooks when it is new
+pub async fn record_version(embed: &EmbedData, database: &Database, hooks: bool) {
+	let Some(version) = &embed.version else {
+		return;
+	};
+
+	let known = match database.get_versions().await {
+		Ok(known) => known,
+		Err(e) => {
+			error!("{e}");
+			return;
+		}
+	};
+	let game_version = GameVersion {
+		version: version.clone(),
+		major: is_major(version, known.iter().map(|known| known.version.as_str())),
+		release_date: embed.published,
+		url: embed.url.clone(),
+		seen_date: chrono::Utc::now().timestamp(),
+	};
+	match database.store_version(&game_version).await {
+		Ok(true) => {}
+		Ok(false) => {
+			return;
+		}
+		Err(e) => {
+			error!("{e}");
+			return;
+		}
+	}
+	warn!("Recorded game version {version}");
+
+	if hooks {
+		let notice = if game_version.major {
+			format!("Major update {version} was released")
+		} else {
+			format!("Hotfix {version} was released")
+		};
+		embed.handle_announcement("New version", &notice, |notify| notify.versions).await;
+	}
+}
+
+/// Versions are major when no other version of their major.minor series is known, later ones are hotfixes
+fn is_major<'a>(version: &str, known: impl IntoIterator<Item = &'a str>) -> bool {
+	let series = |version: &str| version_parts(version).into_iter().take(2).collect::<Vec<_>>();
+	let own = series(version);
+	known.into_iter()
+		.filter(|known| compare_versions(known, version) == Ordering::Less)
+		.all(|known| series(known) != own)
+}
+
+/// Orders dotted versions by their numbers, such that "2.9" is lower than "2.10"
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+	version_parts(a).cmp(&version_parts(b))
+}
+
+fn version_parts(version: &str) -> Vec<u64> {
+	version.split('.').map(|part| part.parse().unwrap_or(0)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use std::cmp::Ordering;
+
+	use crate::scrapers::versions::{compare_versions, is_major};
+
+	#[test]
+	fn test_major_and_hotfix() {
+		let known = ["2.19.0.29", "2.21.0.35", "2.21.1.21"];
+		assert_eq!(compare_versions("2.9.0.1", "2.10.0.1"), Ordering::Less);
+		assert!(is_major("2.23.0.10", known));
+		assert!(!is_major("2.21.1.22", known));
+		// A changelog of the release itself may only be found after one of its hotfixes
+		assert!(is_major("2.21.0.35", ["2.21.1.21"]));
+		assert!(is_major("2.21.0.35", []));
+	}
+}
\ No newline at end of file
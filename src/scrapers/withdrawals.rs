@@ -0,0 +1,106 @@
+use tracing::{error, warn};
+
+use crate::api::database::Database;
+use crate::CONFIG;
+use crate::embed::{EmbedData, EMPTY_IMG};
+use crate::json::sources::{NewsArticle, Source};
+use crate::scrapers::news_source::SourceRegistry;
+
+/// Withdraws recent articles which disappeared from the listing, whether or not their page still answers
+pub async fn check_unlisted(source: &mut Source, registry: &SourceRegistry, listed: &[String], database: &Database, hooks: bool) {
+	let since = chrono::Utc::now().timestamp() - CONFIG.updates.window * 60 * 60;
+	source.unlisted.retain(|url| !listed.contains(url));
+
+	for url in vanished(&source.tracked_urls, listed, since) {
+		// Every article is withdrawn once, for as long as it stays unlisted
+		if source.unlisted.insert(url.clone()) {
+			withdraw(source, registry, &url, database, hooks).await;
+		}
+	}
+}
+
+/// Articles seen after `since` which are no longer listed, although an article seen before them still is
+fn vanished(tracked: &NewsArticle, listed: &[String], since: i64) -> Vec<String> {
+	// Listings drop their oldest articles first, which are therefore not suspicious
+	let Some(oldest_listed) = listed.iter().filter_map(|url| tracked.get(url)).min().copied() else {
+		return vec![];
+	};
+	tracked.iter()
+		.filter(|(url, seen)| **seen > oldest_listed && **seen >= since && !listed.contains(url))
+		.map(|(url, _)| url.clone())
+		.collect()
+}
+
+/// Records that an article was taken down and tells opted-in webhooks, once per article
+pub async fn withdraw(source: &Source, registry: &SourceRegistry, url: &str, database: &Database, hooks: bool) {
+	match database.mark_removed(url, source.id).await {
+		Ok(true) => {}
+		Ok(false) => {
+			return;
+		}
+		Err(e) => {
+			error!("{e}");
+			return;
+		}
+	}
+	warn!("{url} was taken down");
+
+	if let (true, Ok(news_source)) = (hooks, registry.resolve(source)) {
+		let title = database.get_content(url, source.id).await.ok().flatten()
+			.and_then(|content| content.title)
+			.unwrap_or(url.to_owned());
+		let mut embed = EmbedData::new(&title, url, EMPTY_IMG, "", source.scrape_type);
+		embed.language = source.language.clone();
+		embed.handle_notice("Withdrawn", "This article was taken down and is no longer available", news_source, source, |notify| notify.withdrawals).await;
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::api::database::Database;
+	use crate::json::sources::{NewsArticle, Sources};
+	use crate::scrapers::news_source::SourceRegistry;
+	use crate::scrapers::withdrawals::{check_unlisted, vanished};
+
+	#[test]
+	fn test_only_newer_articles_vanish() {
+		let tracked: NewsArticle = [("a", 100), ("b", 200), ("c", 300), ("d", 400), ("old", 10)].into_iter()
+			.map(|(url, seen)| (url.to_owned(), seen))
+			.collect();
+		let listed = ["d".to_owned(), "b".to_owned()];
+
+		// "a" was pushed off the listing, "c" was removed from between listed articles
+		assert_eq!(vanished(&tracked, &listed, 50), vec!["c".to_owned()]);
+		assert!(vanished(&tracked, &listed, 350).is_empty());
+		assert!(vanished(&tracked, &[], 0).is_empty());
+	}
+
+	#[tokio::test]
+	async fn test_unlisted_articles_are_withdrawn() {
+		let path = std::env::temp_dir().join(format!("wt_event_handler_withdrawals_{}.sqlite", std::process::id()));
+		let db = Database::new(path.to_str().unwrap()).await.unwrap();
+		let registry = SourceRegistry::default();
+		let raw = br#"{"sources": [{"name": "news", "domain": "https://example.com/feed", "id": 0, "scrape_type": "Main", "kind": "feed"}]}"#;
+		let mut source = Sources::from_slice(raw, &registry).unwrap().sources.remove(0);
+
+		let now = chrono::Utc::now().timestamp();
+		db.store_recent(["a", "b", "c"], source.id).await.unwrap();
+		source.tracked_urls = [("a", now - 300), ("b", now - 200), ("c", now - 100)].into_iter()
+			.map(|(url, seen)| (url.to_owned(), seen))
+			.collect();
+
+		// "b" was pulled from the listing, even though its page may still answer
+		let listed = ["c".to_owned(), "a".to_owned()];
+		check_unlisted(&mut source, &registry, &listed, &db, false).await;
+		let removed: Vec<String> = sqlx::query_scalar("SELECT url FROM sources WHERE removed_date IS NOT NULL").fetch_all(&db.connection).await.unwrap();
+		assert_eq!(removed, vec!["b".to_owned()]);
+		assert!(source.unlisted.contains("b"));
+
+		// Articles listed again are no longer considered unlisted
+		check_unlisted(&mut source, &registry, &["c".to_owned(), "b".to_owned(), "a".to_owned()], &db, false).await;
+		assert!(source.unlisted.is_empty());
+
+		drop(db);
+		drop(std::fs::remove_file(path));
+	}
+}
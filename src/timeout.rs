@@ -20,12 +20,12 @@ impl Timeout {
 		STATS.lock().await.increment(Incr::Timeouts);
 	}
 	pub fn is_timed_out(&self, source: &str) -> bool {
-		return if let Some(time) = self.blocked.get(source) {
+		if let Some(time) = self.blocked.get(source) {
 			let now = chrono::Utc::now().timestamp();
 
 			now < *time
 		} else {
 			false
-		};
+		}
 	}
 }
\ No newline at end of file
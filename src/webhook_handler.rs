@@ -6,7 +6,6 @@ use tracing::{error, warn};
 
 use crate::embed::EmbedData;
 use crate::json::webhooks::{FilterType, Hooks};
-use crate::scrapers::scraper_resources::resources::ScrapeType;
 use crate::WEBHOOK_AUTH;
 
 const DEFAULT_KEYWORDS: [&str; 30] = [
@@ -16,18 +15,8 @@ const DEFAULT_KEYWORDS: [&str; 30] = [
 	"issues", "technical", "servers", "christmas", "market", "camouflages"
 ];
 
-pub fn match_filter(content: &str, hook: &Hooks, scrape_type: ScrapeType) -> bool {
-	match scrape_type {
-		ScrapeType::Main | ScrapeType::Changelog => {
-			filter_main(content, hook)
-		}
-		ScrapeType::Forum => {
-			filter_forum(content, hook)
-		}
-	}
-}
-
-fn filter_main(content: &str, hook: &Hooks) -> bool {
+/// Applies the main filter and keywords of the hook
+pub fn filter_main(content: &str, hook: &Hooks) -> bool {
 	let main_filter = &hook.main_filter;
 
 	match main_filter {
@@ -70,7 +59,8 @@ fn filter_main(content: &str, hook: &Hooks) -> bool {
 	}
 }
 
-fn filter_forum(content: &str, hook: &Hooks) -> bool {
+/// Applies the forum filter and keywords of the hook
+pub fn filter_forum(content: &str, hook: &Hooks) -> bool {
 	let forum_filter = &hook.forum_filter;
 
 	match forum_filter {
@@ -115,6 +105,15 @@ fn filter_forum(content: &str, hook: &Hooks) -> bool {
 
 /// Ships webhook and builds embed
 pub async fn deliver_webhook(content: EmbedData, pos: usize) {
+	ship_embed(&content.title, &content.preview_text, &content, pos).await;
+}
+
+/// Ships a notice about an already delivered article, such as "Updated" with a summary of the edit
+pub async fn deliver_notice(content: &EmbedData, label: &str, notice: &str, pos: usize) {
+	ship_embed(&format!("{label}: {}", content.title), notice, content, pos).await;
+}
+
+async fn ship_embed(title: &str, description: &str, content: &EmbedData, pos: usize) {
 	let uid = &WEBHOOK_AUTH.hooks[pos].uid;
 	let token = &WEBHOOK_AUTH.hooks[pos].token;
 
@@ -129,9 +128,18 @@ pub async fn deliver_webhook(content: EmbedData, pos: usize) {
 	};
 
 	let embed = Embed::fake(|e| {
-		e.title(&content.title)
+		if let Some(author) = &content.author {
+			e.author(|a| {
+				a.name(author.display_name());
+				if let Some(avatar) = &author.avatar {
+					a.icon_url(avatar);
+				}
+				a
+			});
+		}
+		e.title(title)
 		 .color(Color::from_rgb(116, 16, 210))
-		 .description(&content.preview_text)
+		 .description(description)
 		 .thumbnail("https://avatars.githubusercontent.com/u/97326911?s=40&v=4")
 		 .image(&content.img_url)
 		 .url(&content.url)
@@ -143,7 +151,7 @@ pub async fn deliver_webhook(content: EmbedData, pos: usize) {
 	});
 
 	webhook.execute(my_http_client, false, |w| {
-		w.content(&format!("[{}]({})", &content.title, &content.url));
+		w.content(format!("[{}]({})", title, &content.url));
 		w.embeds(vec![embed]);
 		w
 	}).await.unwrap();
@@ -153,17 +161,25 @@ pub async fn deliver_webhook(content: EmbedData, pos: usize) {
 // Tests  -----------------------------------------------------------------------
 
 #[cfg(test)]
+// Reason: Spells out which outcome each filter test expects
+#[allow(clippy::bool_assert_comparison)]
 mod tests {
 	#[allow(unused_imports)]
 	use crate::json::webhooks::FilterType::{Blacklist, Whitelist};
 
+	#[allow(unused_imports)]
+	use crate::embed::DEFAULT_LANGUAGE;
+
+	#[allow(unused_imports)]
+	use crate::json::webhooks::{Notifications, Reminders};
+
 	#[allow(unused_imports)]
 	use super::*;
 
 	// Main tests -------------------------------------------------------------------
 	#[test]
 	fn main_test_filter_default_pass() {
-		assert_eq!(match_filter("pass", &Hooks {
+		assert_eq!(filter_main("pass", &Hooks {
 			name: String::new(),
 			token: String::new(),
 			uid: 0,
@@ -171,12 +187,15 @@ mod tests {
 			forum_filter: FilterType::default(),
 			main_keywords: vec![],
 			forum_keywords: vec![],
-		}, ScrapeType::Main), true)
+			languages: vec![DEFAULT_LANGUAGE.to_owned()],
+			notify: Notifications::default(),
+			reminders: Reminders::default(),
+		}), true)
 	}
 
 	#[test]
 	fn main_test_filter_default_no_match() {
-		assert_eq!(match_filter("xyz", &Hooks {
+		assert_eq!(filter_main("xyz", &Hooks {
 			name: String::new(),
 			token: String::new(),
 			uid: 0,
@@ -184,12 +203,15 @@ mod tests {
 			forum_filter: FilterType::default(),
 			main_keywords: vec![],
 			forum_keywords: vec![],
-		}, ScrapeType::Main), false);
+			languages: vec![DEFAULT_LANGUAGE.to_owned()],
+			notify: Notifications::default(),
+			reminders: Reminders::default(),
+		}), false);
 	}
 
 	#[test]
 	fn main_test_filter_whitelist_match() {
-		assert_eq!(match_filter("C", &Hooks {
+		assert_eq!(filter_main("C", &Hooks {
 			name: String::new(),
 			token: String::new(),
 			uid: 0,
@@ -197,13 +219,16 @@ mod tests {
 			forum_filter: Blacklist,
 			main_keywords: vec!["A".to_owned(), "B".to_owned(), "C".to_owned(), "D".to_owned()],
 			forum_keywords: vec!["W".to_owned(), "X".to_owned(), "Y".to_owned(), "Z".to_owned()],
-		}, ScrapeType::Main), true);
+			languages: vec![DEFAULT_LANGUAGE.to_owned()],
+			notify: Notifications::default(),
+			reminders: Reminders::default(),
+		}), true);
 	}
 
 	#[test]
 	#[should_panic]
 	fn main_test_filter_whitelist_miss() {
-		assert_eq!(match_filter("E", &Hooks {
+		assert_eq!(filter_main("E", &Hooks {
 			name: String::new(),
 			token: String::new(),
 			uid: 0,
@@ -211,13 +236,16 @@ mod tests {
 			forum_filter: Whitelist,
 			main_keywords: vec!["A".to_owned(), "B".to_owned(), "C".to_owned(), "D".to_owned()],
 			forum_keywords: vec!["W".to_owned(), "X".to_owned(), "Y".to_owned(), "Z".to_owned()],
-		}, ScrapeType::Main), true);
+			languages: vec![DEFAULT_LANGUAGE.to_owned()],
+			notify: Notifications::default(),
+			reminders: Reminders::default(),
+		}), true);
 	}
 
 	#[test]
 	#[should_panic]
 	fn main_test_filter_blacklist_match() {
-		assert_eq!(match_filter("C", &Hooks {
+		assert_eq!(filter_main("C", &Hooks {
 			name: String::new(),
 			token: String::new(),
 			uid: 0,
@@ -225,12 +253,15 @@ mod tests {
 			forum_filter: Blacklist,
 			main_keywords: vec!["A".to_owned(), "B".to_owned(), "C".to_owned(), "D".to_owned()],
 			forum_keywords: vec!["A".to_owned(), "B".to_owned(), "C".to_owned(), "D".to_owned()],
-		}, ScrapeType::Main), true);
+			languages: vec![DEFAULT_LANGUAGE.to_owned()],
+			notify: Notifications::default(),
+			reminders: Reminders::default(),
+		}), true);
 	}
 
 	#[test]
 	fn main_test_filter_blacklist_miss() {
-		assert_eq!(match_filter("E", &Hooks {
+		assert_eq!(filter_main("E", &Hooks {
 			name: String::new(),
 			token: String::new(),
 			uid: 0,
@@ -238,14 +269,17 @@ mod tests {
 			forum_filter: Blacklist,
 			main_keywords: vec!["A".to_owned(), "B".to_owned(), "C".to_owned(), "D".to_owned()],
 			forum_keywords: vec!["A".to_owned(), "B".to_owned(), "C".to_owned(), "D".to_owned()],
-		}, ScrapeType::Main), true);
+			languages: vec![DEFAULT_LANGUAGE.to_owned()],
+			notify: Notifications::default(),
+			reminders: Reminders::default(),
+		}), true);
 	}
 
 	// forum tests ------------------------------------------------------------------
 
 	#[test]
 	fn forum_test_filter_default_pass() {
-		assert_eq!(match_filter("pass", &Hooks {
+		assert_eq!(filter_forum("pass", &Hooks {
 			name: String::new(),
 			token: String::new(),
 			uid: 0,
@@ -253,12 +287,15 @@ mod tests {
 			forum_filter: FilterType::default(),
 			main_keywords: vec![],
 			forum_keywords: vec![],
-		}, ScrapeType::Forum), true)
+			languages: vec![DEFAULT_LANGUAGE.to_owned()],
+			notify: Notifications::default(),
+			reminders: Reminders::default(),
+		}), true)
 	}
 
 	#[test]
 	fn forum_test_filter_default_no_match() {
-		assert_eq!(match_filter("xyz", &Hooks {
+		assert_eq!(filter_forum("xyz", &Hooks {
 			name: String::new(),
 			token: String::new(),
 			uid: 0,
@@ -266,12 +303,15 @@ mod tests {
 			forum_filter: FilterType::default(),
 			main_keywords: vec![],
 			forum_keywords: vec![],
-		}, ScrapeType::Forum), false);
+			languages: vec![DEFAULT_LANGUAGE.to_owned()],
+			notify: Notifications::default(),
+			reminders: Reminders::default(),
+		}), false);
 	}
 
 	#[test]
 	fn forum_test_filter_whitelist_match() {
-		assert_eq!(match_filter("C", &Hooks {
+		assert_eq!(filter_forum("C", &Hooks {
 			name: String::new(),
 			token: String::new(),
 			uid: 0,
@@ -279,12 +319,15 @@ mod tests {
 			forum_filter: Blacklist,
 			main_keywords: vec!["A".to_owned(), "B".to_owned(), "C".to_owned(), "D".to_owned()],
 			forum_keywords: vec!["W".to_owned(), "X".to_owned(), "Y".to_owned(), "Z".to_owned()],
-		}, ScrapeType::Forum), true);
+			languages: vec![DEFAULT_LANGUAGE.to_owned()],
+			notify: Notifications::default(),
+			reminders: Reminders::default(),
+		}), true);
 	}
 
 	#[test]
 	fn forum_test_filter_whitelist_miss() {
-		assert_eq!(match_filter("E", &Hooks {
+		assert_eq!(filter_forum("E", &Hooks {
 			name: String::new(),
 			token: String::new(),
 			uid: 0,
@@ -292,12 +335,15 @@ mod tests {
 			forum_filter: Whitelist,
 			main_keywords: vec!["A".to_owned(), "B".to_owned(), "C".to_owned(), "D".to_owned()],
 			forum_keywords: vec!["W".to_owned(), "X".to_owned(), "Y".to_owned(), "Z".to_owned()],
-		}, ScrapeType::Forum), false);
+			languages: vec![DEFAULT_LANGUAGE.to_owned()],
+			notify: Notifications::default(),
+			reminders: Reminders::default(),
+		}), false);
 	}
 
 	#[test]
 	fn forum_test_filter_blacklist_match() {
-		assert_eq!(match_filter("C", &Hooks {
+		assert_eq!(filter_forum("C", &Hooks {
 			name: String::new(),
 			token: String::new(),
 			uid: 0,
@@ -305,12 +351,15 @@ mod tests {
 			forum_filter: Blacklist,
 			main_keywords: vec!["A".to_owned(), "B".to_owned(), "C".to_owned(), "D".to_owned()],
 			forum_keywords: vec!["A".to_owned(), "B".to_owned(), "C".to_owned(), "D".to_owned()],
-		}, ScrapeType::Forum), false);
+			languages: vec![DEFAULT_LANGUAGE.to_owned()],
+			notify: Notifications::default(),
+			reminders: Reminders::default(),
+		}), false);
 	}
 
 	#[test]
 	fn forum_test_filter_blacklist_miss() {
-		match_filter("E", &Hooks {
+		filter_forum("E", &Hooks {
 			name: String::new(),
 			token: String::new(),
 			uid: 0,
@@ -318,6 +367,9 @@ mod tests {
 			forum_filter: Blacklist,
 			main_keywords: vec!["A".to_owned(), "B".to_owned(), "C".to_owned(), "D".to_owned()],
 			forum_keywords: vec!["A".to_owned(), "B".to_owned(), "C".to_owned(), "D".to_owned()],
-		}, ScrapeType::Forum);
+			languages: vec![DEFAULT_LANGUAGE.to_owned()],
+			notify: Notifications::default(),
+			reminders: Reminders::default(),
+		});
 	}
 }